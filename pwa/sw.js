@@ -0,0 +1,123 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+// Caches the app shell, so that the app can start without a network connection.
+// The WebSocket connection to the bot is never cached - it is not seen by the service worker.
+
+const CACHE_NAME = "acme-bot-remote-v1";
+
+const STATIC_ASSETS = [
+  "./",
+  "./offline.html",
+  "./manifest.webmanifest",
+  "./icon-192.png",
+  "./icon-512.png",
+  "./PublicSans-df270da50e1ae431.woff2",
+];
+
+// Trunk puts content hashes in the bundle file names, so find them in the page itself.
+const ASSET_PATTERN = /(?:href|src)="([^"]+\.(?:css|js|wasm))"/g;
+
+// File names with a content hash never change, any other file may be updated in place.
+const HASHED_PATTERN = /-[0-9a-f]{16}(?:_bg)?\.\w+$/;
+
+function bundleAssets(html) {
+  return Array.from(html.matchAll(ASSET_PATTERN), (match) => match[1]);
+}
+
+function absolute(path) {
+  return new URL(path, self.registration.scope).href;
+}
+
+// Remove the bundles of previous builds, which the current page no longer refers to.
+async function pruneBundles(html) {
+  const keep = new Set([...STATIC_ASSETS, ...bundleAssets(html)].map(absolute));
+  const cache = await caches.open(CACHE_NAME);
+  const stale = (await cache.keys()).filter((request) => {
+    const url = new URL(request.url);
+    return HASHED_PATTERN.test(url.pathname) && !keep.has(url.href);
+  });
+  await Promise.all(stale.map((request) => cache.delete(request)));
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil((async () => {
+    const response = await fetch("./", { cache: "no-cache" });
+    const cache = await caches.open(CACHE_NAME);
+    await cache.addAll([...STATIC_ASSETS, ...bundleAssets(await response.text())]);
+  })());
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil((async () => {
+    const names = await caches.keys();
+    await Promise.all(names.filter((name) => name !== CACHE_NAME).map((name) => caches.delete(name)));
+  })());
+});
+
+async function fromNetwork(request, cacheKey) {
+  const response = await fetch(request);
+  if (response.ok) {
+    const cache = await caches.open(CACHE_NAME);
+    await cache.put(cacheKey, response.clone());
+  }
+  return response;
+}
+
+// Only the app root serves the app shell, other pages (if any) are not cached.
+function isAppRoot(url) {
+  return url.pathname === new URL(self.registration.scope).pathname;
+}
+
+// Pages: prefer the network, so that new builds are picked up as soon as possible.
+async function handleNavigation(event) {
+  const { request } = event;
+  const url = new URL(request.url);
+  if (!isAppRoot(url)) {
+    return fetch(request);
+  }
+  // Session links carry an access code in the query string, so keep it out of the cache key.
+  url.search = "";
+  try {
+    const response = await fromNetwork(request, url.href);
+    if (response.ok) {
+      event.waitUntil(response.clone().text().then(pruneBundles));
+    }
+    return response;
+  } catch {
+    return (await caches.match(url.href)) ?? (await caches.match("./offline.html"));
+  }
+}
+
+// Bundle files are immutable, so the cached copy is always good.
+async function handleBundle(request) {
+  return (await caches.match(request)) ?? (await fromNetwork(request, request));
+}
+
+// Other files keep their names between builds, so prefer the network like for pages.
+async function handleAsset(request) {
+  try {
+    return await fromNetwork(request, request);
+  } catch (e) {
+    const cached = await caches.match(request);
+    if (cached) {
+      return cached;
+    }
+    throw e;
+  }
+}
+
+self.addEventListener("fetch", (event) => {
+  const { request } = event;
+  const url = new URL(request.url);
+  if (request.method !== "GET" || url.origin !== self.location.origin) {
+    return;
+  }
+  if (request.mode === "navigate") {
+    event.respondWith(handleNavigation(event));
+  } else if (HASHED_PATTERN.test(url.pathname)) {
+    event.respondWith(handleBundle(request));
+  } else {
+    event.respondWith(handleAsset(request));
+  }
+});
@@ -0,0 +1,146 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::i18n::Messages;
+
+pub static MESSAGES: Messages = Messages {
+    next_up: "Next up",
+    show_settings: "Show settings",
+    show_copyright: "Show copyright info",
+    show_source: "Show source code",
+    show_licenses: "Show OSS licenses",
+    close: "Close",
+
+    filter_queue: "Filter queue",
+    filter_owner: "Filter by uploader or requester",
+    everyone: "Everyone",
+    uploaded_by: |name| format!("Uploaded by {name}"),
+    requested_by: |name| format!("Requested by {name}"),
+    remove_all: |n| format!("Remove all {n}"),
+    select: "Select",
+    done: "Done",
+    selected_count: |n| format!("{n} selected"),
+    move_to_top: "Move to top",
+    move_to_bottom: "Move to bottom",
+
+    play: "Play",
+    remove: "Remove",
+    clear_queue: "Clear queue",
+    previous_track: "Previous track",
+    pause: "Pause",
+    resume: "Resume",
+    next_track: "Next track",
+    loop_queue: "Loop",
+    volume: "Volume",
+    thumbnail_of: |title| format!("Thumbnail of {title}"),
+    now_playing: |title, uploader| format!("Now playing: {title} by {uploader}"),
+    player_playing: "Playing",
+    player_paused: "Paused",
+    player_stopped: "Stopped",
+    player_idle: "Queue is empty",
+    player_disconnected: "Player disconnected",
+    volume_percent: |n| format!("{n}%"),
+
+    undo: "Undo",
+    dismiss: "Dismiss",
+    removed_track: |title| format!("Removed {title}"),
+    removed_tracks: |n| match n {
+        1 => "Removed 1 track".to_string(),
+        _ => format!("Removed {n} tracks"),
+    },
+    queue_cleared: "Queue cleared",
+    not_connected: "Not connected to the player.",
+    command_unsupported: "The bot does not support this command.",
+    command_forbidden: "This session is not allowed to do that.",
+    command_failed: "The command could not be sent.",
+    server_error: |error| format!("Server error: {error}"),
+    confirm_clear: "Remove all tracks from the queue?",
+    confirm_remove: |n| match n {
+        1 => "Remove this track from the queue?".to_string(),
+        _ => format!("Remove {n} tracks from the queue?"),
+    },
+
+    offline: "Offline",
+    offline_info: "The remote needs a network connection to control the player. \
+        It will reconnect when you are back online.",
+
+    sessions: "Sessions",
+    no_saved_sessions: "No saved sessions yet.",
+    forget_session: "Forget",
+    session_name: "Name",
+    optional: "Optional",
+    discord_link: "Link from Discord",
+    add_session: "Add session",
+    switch_session: "Switch session",
+    manage_sessions: "Manage sessions...",
+    save_session: "Save session",
+    invalid_link: "This is not a valid session link.",
+    session_expired: "The session link is invalid or has expired.",
+    paste_new_link: "Paste a new link from Discord to reconnect:",
+    reconnect: "Reconnect",
+
+    pair_device: "Pair a device",
+    pair_device_info: "Scan the code with another device to open this session there.",
+    qr_code_label: "QR code of the session link",
+    qr_code_too_long: "The link is too long to be shown as a QR code.",
+    use_short_lived_link: "Use a short-lived link",
+    link_expires_in: |min| match min {
+        1 => "This link expires in 1 minute.".to_string(),
+        _ => format!("This link expires in {min} minutes."),
+    },
+    theme: "Theme",
+    theme_system: "System",
+    theme_light: "Light",
+    theme_dark: "Dark",
+    theme_contrast: "High contrast",
+    language: "Language",
+    language_auto: "Browser default",
+    notifications: "Notifications",
+    notify_track_change: "Notify me when a new track starts playing",
+
+    show_now_playing: "Show now playing",
+    now_playing_title: "Now playing",
+    nothing_playing: "Nothing is playing.",
+    up_next: "Up next",
+    playback_progress: "Playback progress",
+
+    show_details: "Show details",
+    details_uploader: "Uploader",
+    details_duration: "Duration",
+    details_link: "Link",
+    details_id: "ID",
+    details_extractor: "Extractor",
+    details_requester: "Requested by",
+    details_position: "Position in queue",
+    starts_at: "Starts at",
+    started_at: "Started at",
+    approximately: |time| format!("about {time}"),
+    copy_link: "Copy link",
+    link_copied: "Copied!",
+
+    queue_summary: |n, total| match n {
+        1 => format!("1 track, {total}"),
+        _ => format!("{n} tracks, {total}"),
+    },
+    queue_repeats: "repeats",
+
+    export_queue: "Export",
+    export_info: "Save the current track and the queue as a playlist file.",
+    export_failed: "Cannot export the queue.",
+
+    import_queue: "Import",
+    import_info: "Choose or drop a playlist file onto the remote. \
+        M3U, XSPF and JSON playlists and lists of web addresses are supported.",
+    import_preview: |n| match n {
+        1 => "1 track will be added to the queue:".to_string(),
+        _ => format!("{n} tracks will be added to the queue:"),
+    },
+    import_empty: "No tracks were found in the file.",
+    import_skipped: "These lines were skipped:",
+    invalid_url_line: |line| format!("Line {line}: not a web address"),
+    unclosed_track_line: |line| format!("Line {line}: the track element is not closed"),
+    missing_location_line: |line| format!("Line {line}: the track has no location"),
+    invalid_json_line: |line| format!("Line {line}: not a list of tracks"),
+    add_to_queue: "Add to queue",
+    cancel: "Cancel",
+};
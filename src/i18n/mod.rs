@@ -0,0 +1,307 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codee::string::JsonSerdeCodec;
+use leptos::logging;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsValue;
+use leptos::web_sys::js_sys::{Array, Date, Function, Intl, Object, Reflect};
+use leptos_use::storage::use_local_storage;
+use leptos_use::use_locales;
+use serde::{Deserialize, Serialize};
+
+mod en;
+mod pl;
+
+/// Key of the locale chosen by the user in `localStorage`.
+pub const LOCALE_KEY: &str = "acme-bot-remote.locale";
+
+/// Languages written from right to left.
+const RTL_LANGUAGES: [&str; 4] = ["ar", "fa", "he", "ur"];
+
+/// Locale of the user interface.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Pl,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Pl];
+
+    /// Get the BCP 47 language tag of the locale.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Pl => "pl",
+        }
+    }
+
+    /// Get the name of the locale, in its own language.
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::Pl => "Polski",
+        }
+    }
+
+    /// Get the text direction of the locale, as used by the `dir` attribute.
+    pub fn dir(self) -> &'static str {
+        if RTL_LANGUAGES.contains(&self.tag()) {
+            "rtl"
+        } else {
+            "ltr"
+        }
+    }
+
+    /// Get the message catalog of the locale.
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Locale::En => &en::MESSAGES,
+            Locale::Pl => &pl::MESSAGES,
+        }
+    }
+
+    /// Pick the first supported locale from the user's preferred languages.
+    pub fn negotiate(languages: &[String]) -> Self {
+        languages
+            .iter()
+            .filter_map(|tag| {
+                let language = tag.split('-').next().unwrap_or(tag);
+                Locale::ALL
+                    .into_iter()
+                    .find(|l| l.tag().eq_ignore_ascii_case(language))
+            })
+            .next()
+            .unwrap_or_default()
+    }
+}
+
+/// Translated user interface strings.
+pub struct Messages {
+    pub next_up: &'static str,
+    pub show_settings: &'static str,
+    pub show_copyright: &'static str,
+    pub show_source: &'static str,
+    pub show_licenses: &'static str,
+    pub close: &'static str,
+
+    pub filter_queue: &'static str,
+    pub filter_owner: &'static str,
+    pub everyone: &'static str,
+    pub uploaded_by: fn(&str) -> String,
+    pub requested_by: fn(&str) -> String,
+    pub remove_all: fn(usize) -> String,
+    pub select: &'static str,
+    pub done: &'static str,
+    pub selected_count: fn(usize) -> String,
+    pub move_to_top: &'static str,
+    pub move_to_bottom: &'static str,
+
+    pub play: &'static str,
+    pub remove: &'static str,
+    pub clear_queue: &'static str,
+    pub previous_track: &'static str,
+    pub pause: &'static str,
+    pub resume: &'static str,
+    pub next_track: &'static str,
+    pub loop_queue: &'static str,
+    pub volume: &'static str,
+    pub thumbnail_of: fn(&str) -> String,
+    pub now_playing: fn(&str, &str) -> String,
+    pub player_playing: &'static str,
+    pub player_paused: &'static str,
+    pub player_stopped: &'static str,
+    pub player_idle: &'static str,
+    pub player_disconnected: &'static str,
+    pub volume_percent: fn(u8) -> String,
+
+    pub undo: &'static str,
+    pub dismiss: &'static str,
+    pub removed_track: fn(&str) -> String,
+    pub removed_tracks: fn(usize) -> String,
+    pub queue_cleared: &'static str,
+    pub confirm_clear: &'static str,
+    pub confirm_remove: fn(usize) -> String,
+    pub not_connected: &'static str,
+    pub command_unsupported: &'static str,
+    pub command_forbidden: &'static str,
+    pub command_failed: &'static str,
+    pub server_error: fn(&str) -> String,
+
+    pub offline: &'static str,
+    pub offline_info: &'static str,
+
+    pub sessions: &'static str,
+    pub no_saved_sessions: &'static str,
+    pub forget_session: &'static str,
+    pub session_name: &'static str,
+    pub optional: &'static str,
+    pub discord_link: &'static str,
+    pub add_session: &'static str,
+    pub switch_session: &'static str,
+    pub manage_sessions: &'static str,
+    pub save_session: &'static str,
+    pub invalid_link: &'static str,
+    pub session_expired: &'static str,
+    pub paste_new_link: &'static str,
+    pub reconnect: &'static str,
+
+    pub pair_device: &'static str,
+    pub pair_device_info: &'static str,
+    pub qr_code_label: &'static str,
+    pub qr_code_too_long: &'static str,
+    pub use_short_lived_link: &'static str,
+    pub link_expires_in: fn(i64) -> String,
+    pub theme: &'static str,
+    pub theme_system: &'static str,
+    pub theme_light: &'static str,
+    pub theme_dark: &'static str,
+    pub theme_contrast: &'static str,
+    pub language: &'static str,
+    pub language_auto: &'static str,
+    pub notifications: &'static str,
+    pub notify_track_change: &'static str,
+
+    pub show_now_playing: &'static str,
+    pub now_playing_title: &'static str,
+    pub nothing_playing: &'static str,
+    pub up_next: &'static str,
+    pub playback_progress: &'static str,
+
+    pub show_details: &'static str,
+    pub details_uploader: &'static str,
+    pub details_duration: &'static str,
+    pub details_link: &'static str,
+    pub details_id: &'static str,
+    pub details_extractor: &'static str,
+    pub details_requester: &'static str,
+    pub details_position: &'static str,
+    pub starts_at: &'static str,
+    pub started_at: &'static str,
+    pub approximately: fn(&str) -> String,
+    pub copy_link: &'static str,
+    pub link_copied: &'static str,
+
+    pub queue_summary: fn(usize, &str) -> String,
+    pub queue_repeats: &'static str,
+
+    pub export_queue: &'static str,
+    pub export_info: &'static str,
+    pub export_failed: &'static str,
+
+    pub import_queue: &'static str,
+    pub import_info: &'static str,
+    pub import_preview: fn(usize) -> String,
+    pub import_empty: &'static str,
+    pub import_skipped: &'static str,
+    pub invalid_url_line: fn(usize) -> String,
+    pub unclosed_track_line: fn(usize) -> String,
+    pub missing_location_line: fn(usize) -> String,
+    pub invalid_json_line: fn(usize) -> String,
+    pub add_to_queue: &'static str,
+    pub cancel: &'static str,
+}
+
+#[derive(Clone, Copy)]
+struct LocaleContext(Signal<Locale>);
+
+/// Select the locale of the app and provide it to the child components.
+///
+/// The locale chosen by the user takes precedence over the browser languages.
+/// The `lang` and `dir` attributes of the document follow the selected locale.
+pub fn provide_locale() {
+    let (chosen, _, _) = use_local_storage::<Option<Locale>, JsonSerdeCodec>(LOCALE_KEY);
+    let languages = use_locales();
+    let locale = Memo::new(move |_| {
+        chosen
+            .get()
+            .unwrap_or_else(|| languages.with(|l| Locale::negotiate(l)))
+    });
+    Effect::new(move |_| {
+        let locale = locale.get();
+        let Some(root) = document().document_element() else {
+            return;
+        };
+        let result = root
+            .set_attribute("lang", locale.tag())
+            .and_then(|_| root.set_attribute("dir", locale.dir()));
+        if let Err(e) = result {
+            logging::error!("Cannot apply locale: {:?}", e);
+        }
+    });
+    provide_context(LocaleContext(locale.into()));
+}
+
+/// Get the locale provided by [`provide_locale`].
+pub fn use_locale() -> Signal<Locale> {
+    use_context::<LocaleContext>()
+        .map(|LocaleContext(locale)| locale)
+        .unwrap_or_else(|| Signal::stored(Locale::default()))
+}
+
+/// Get the message catalog of the locale provided by [`provide_locale`].
+pub fn use_messages() -> Signal<&'static Messages> {
+    let locale = use_locale();
+    Signal::derive(move || locale.get().messages())
+}
+
+thread_local! {
+    /// Number formatters by locale and digit count, which are costly to create.
+    static DIGIT_FORMATTERS: RefCell<HashMap<(Locale, u32), Function>> = RefCell::default();
+}
+
+/// Get a formatter for integers padded with zeros to the given number of digits.
+fn digit_formatter(locale: Locale, digits: u32) -> Function {
+    DIGIT_FORMATTERS.with_borrow_mut(|formatters| {
+        let formatter = formatters.entry((locale, digits)).or_insert_with(|| {
+            let options = Object::new();
+            let _ = Reflect::set(&options, &"minimumIntegerDigits".into(), &digits.into());
+            let _ = Reflect::set(&options, &"useGrouping".into(), &false.into());
+            let locales = Array::of1(&locale.tag().into());
+            Intl::NumberFormat::new(&locales, &options).format()
+        });
+        formatter.clone()
+    })
+}
+
+/// Format the duration as `h:mm:ss` or `m:ss`, using the digits of the locale.
+pub fn format_duration(duration: &Duration, locale: Locale) -> String {
+    let format = |f: &Function, n: u64| {
+        f.call1(&JsValue::NULL, &(n as f64).into())
+            .ok()
+            .and_then(|s| s.as_string())
+            .unwrap_or_else(|| n.to_string())
+    };
+    let (one, two) = (digit_formatter(locale, 1), digit_formatter(locale, 2));
+    let mut sec = duration.as_secs();
+    let mut min = sec / 60;
+    let hrs = min / 60;
+    min %= 60;
+    sec %= 60;
+    if hrs > 0 {
+        format!(
+            "{}:{}:{}",
+            format(&one, hrs),
+            format(&two, min),
+            format(&two, sec)
+        )
+    } else {
+        format!("{}:{}", format(&one, min), format(&two, sec))
+    }
+}
+
+/// Format a point in time, given in milliseconds since the epoch, as a time of day.
+pub fn format_time(timestamp: f64, locale: Locale) -> String {
+    let options = Object::new();
+    let _ = Reflect::set(&options, &"timeStyle".into(), &"short".into());
+    Date::new(&timestamp.into())
+        .to_locale_time_string_with_options(locale.tag(), &options)
+        .into()
+}
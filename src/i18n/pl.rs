@@ -0,0 +1,166 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::i18n::Messages;
+
+/// Pick the plural form of a Polish noun for the count.
+fn plural(n: i64, one: &'static str, few: &'static str, many: &'static str) -> &'static str {
+    if n == 1 {
+        one
+    } else if (2..=4).contains(&(n % 10)) && !(12..=14).contains(&(n % 100)) {
+        few
+    } else {
+        many
+    }
+}
+
+pub static MESSAGES: Messages = Messages {
+    next_up: "Następne",
+    show_settings: "Pokaż ustawienia",
+    show_copyright: "Pokaż informacje o prawach autorskich",
+    show_source: "Pokaż kod źródłowy",
+    show_licenses: "Pokaż licencje open source",
+    close: "Zamknij",
+
+    filter_queue: "Filtruj kolejkę",
+    filter_owner: "Filtruj według autora lub zamawiającego",
+    everyone: "Wszyscy",
+    uploaded_by: |name| format!("Przesłane przez {name}"),
+    requested_by: |name| format!("Zamówione przez {name}"),
+    remove_all: |n| format!("Usuń wszystkie ({n})"),
+    select: "Zaznacz",
+    done: "Gotowe",
+    selected_count: |n| format!("Zaznaczono: {n}"),
+    move_to_top: "Przenieś na początek",
+    move_to_bottom: "Przenieś na koniec",
+
+    play: "Odtwórz",
+    remove: "Usuń",
+    clear_queue: "Wyczyść kolejkę",
+    previous_track: "Poprzedni utwór",
+    pause: "Wstrzymaj",
+    resume: "Wznów",
+    next_track: "Następny utwór",
+    loop_queue: "Zapętl",
+    volume: "Głośność",
+    thumbnail_of: |title| format!("Miniatura: {title}"),
+    now_playing: |title, uploader| format!("Teraz odtwarzane: {title}, {uploader}"),
+    player_playing: "Odtwarzanie",
+    player_paused: "Wstrzymano",
+    player_stopped: "Zatrzymano",
+    player_idle: "Kolejka jest pusta",
+    player_disconnected: "Odtwarzacz rozłączony",
+    volume_percent: |n| format!("{n}%"),
+
+    undo: "Cofnij",
+    dismiss: "Zamknij",
+    removed_track: |title| format!("Usunięto {title}"),
+    removed_tracks: |n| {
+        let unit = plural(n as i64, "utwór", "utwory", "utworów");
+        format!("Usunięto {n} {unit}")
+    },
+    queue_cleared: "Wyczyszczono kolejkę",
+    not_connected: "Brak połączenia z odtwarzaczem.",
+    command_unsupported: "Bot nie obsługuje tego polecenia.",
+    command_forbidden: "Ta sesja nie ma do tego uprawnień.",
+    command_failed: "Nie udało się wysłać polecenia.",
+    server_error: |error| format!("Błąd serwera: {error}"),
+    confirm_clear: "Usunąć wszystkie utwory z kolejki?",
+    confirm_remove: |n| match n {
+        1 => "Usunąć ten utwór z kolejki?".to_string(),
+        _ => {
+            let unit = plural(n as i64, "utwór", "utwory", "utworów");
+            format!("Usunąć {n} {unit} z kolejki?")
+        }
+    },
+
+    offline: "Offline",
+    offline_info: "Pilot potrzebuje połączenia z siecią, aby sterować odtwarzaczem. \
+        Połączy się ponownie, gdy wrócisz do sieci.",
+
+    sessions: "Sesje",
+    no_saved_sessions: "Brak zapisanych sesji.",
+    forget_session: "Zapomnij",
+    session_name: "Nazwa",
+    optional: "Opcjonalna",
+    discord_link: "Link z Discorda",
+    add_session: "Dodaj sesję",
+    switch_session: "Przełącz sesję",
+    manage_sessions: "Zarządzaj sesjami...",
+    save_session: "Zapisz sesję",
+    invalid_link: "To nie jest prawidłowy link do sesji.",
+    session_expired: "Link do sesji jest nieprawidłowy lub wygasł.",
+    paste_new_link: "Wklej nowy link z Discorda, aby połączyć się ponownie:",
+    reconnect: "Połącz ponownie",
+
+    pair_device: "Sparuj urządzenie",
+    pair_device_info: "Zeskanuj kod innym urządzeniem, aby otworzyć na nim tę sesję.",
+    qr_code_label: "Kod QR linku do sesji",
+    qr_code_too_long: "Link jest zbyt długi, aby pokazać go jako kod QR.",
+    use_short_lived_link: "Użyj linku tymczasowego",
+    link_expires_in: |min| {
+        let unit = plural(min, "minutę", "minuty", "minut");
+        format!("Ten link wygaśnie za {min} {unit}.")
+    },
+    theme: "Motyw",
+    theme_system: "Systemowy",
+    theme_light: "Jasny",
+    theme_dark: "Ciemny",
+    theme_contrast: "Wysoki kontrast",
+    language: "Język",
+    language_auto: "Domyślny przeglądarki",
+    notifications: "Powiadomienia",
+    notify_track_change: "Powiadamiaj o rozpoczęciu nowego utworu",
+
+    show_now_playing: "Pokaż odtwarzany utwór",
+    now_playing_title: "Teraz odtwarzane",
+    nothing_playing: "Nic nie jest odtwarzane.",
+    up_next: "Następne w kolejce",
+    playback_progress: "Postęp odtwarzania",
+
+    show_details: "Pokaż szczegóły",
+    details_uploader: "Autor",
+    details_duration: "Czas trwania",
+    details_link: "Link",
+    details_id: "ID",
+    details_extractor: "Ekstraktor",
+    details_requester: "Dodane przez",
+    details_position: "Pozycja w kolejce",
+    starts_at: "Początek",
+    started_at: "Rozpoczęto",
+    approximately: |time| format!("około {time}"),
+    copy_link: "Kopiuj link",
+    link_copied: "Skopiowano!",
+
+    queue_summary: |n, total| {
+        let unit = plural(n as i64, "utwór", "utwory", "utworów");
+        format!("{n} {unit}, {total}")
+    },
+    queue_repeats: "zapętlona",
+
+    export_queue: "Eksportuj",
+    export_info: "Zapisz bieżący utwór i kolejkę jako plik playlisty.",
+    export_failed: "Nie można wyeksportować kolejki.",
+
+    import_queue: "Importuj",
+    import_info: "Wybierz lub upuść plik playlisty na pilota. \
+        Obsługiwane są playlisty M3U, XSPF i JSON oraz listy adresów internetowych.",
+    import_preview: |n| {
+        let unit = plural(n as i64, "utwór", "utwory", "utworów");
+        let verb = plural(
+            n as i64,
+            "zostanie dodany",
+            "zostaną dodane",
+            "zostanie dodanych",
+        );
+        format!("Do kolejki {verb} {n} {unit}:")
+    },
+    import_empty: "W pliku nie znaleziono żadnych utworów.",
+    import_skipped: "Pominięto następujące wiersze:",
+    invalid_url_line: |line| format!("Wiersz {line}: to nie jest adres internetowy"),
+    unclosed_track_line: |line| format!("Wiersz {line}: element utworu nie jest zamknięty"),
+    missing_location_line: |line| format!("Wiersz {line}: utwór nie ma lokalizacji"),
+    invalid_json_line: |line| format!("Wiersz {line}: to nie jest lista utworów"),
+    add_to_queue: "Dodaj do kolejki",
+    cancel: "Anuluj",
+};
@@ -6,8 +6,11 @@ use leptos_router::components::*;
 
 use crate::ui::Player;
 
+mod i18n;
 mod player;
+mod playlist;
 mod remote_api;
+mod session;
 mod ui;
 
 #[component]
@@ -2,35 +2,167 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
 pub trait Player {
+    /// Apply the action to the given tracks in the queue.
+    fn batch(&self, action: QueueAction, ids: &[String]) -> Result<(), impl CommandError>;
+
     /// Empty the player's queue.
-    fn clear(&self) -> Result<(), impl Error>;
+    fn clear(&self) -> Result<(), impl CommandError>;
+
+    /// Add the tracks at the given URLs to the end of the queue.
+    fn enqueue(&self, urls: &[String]) -> Result<(), impl CommandError>;
 
     /// Move to the given track in the queue.
-    fn move_to(&self, offset: usize, id: &str) -> Result<(), impl Error>;
+    fn move_to(&self, offset: usize, id: &str) -> Result<(), impl CommandError>;
 
     /// Pause the player.
-    fn pause(&self) -> Result<(), impl Error>;
+    fn pause(&self) -> Result<(), impl CommandError>;
 
     /// Play the previous track.
-    fn prev(&self) -> Result<(), impl Error>;
+    fn prev(&self) -> Result<(), impl CommandError>;
 
     /// Remove the given track from the queue.
-    fn remove(&self, offset: usize, id: &str) -> Result<(), impl Error>;
+    fn remove(&self, offset: usize, id: &str) -> Result<(), impl CommandError>;
+
+    /// Remove all tracks matching the filter from the queue.
+    fn remove_where(&self, filter: &RemoveFilter) -> Result<(), impl CommandError>;
+
+    /// Put removed tracks back into the queue, at their original offsets.
+    fn restore<T: TrackSnapshot>(&self, tracks: &[(usize, T)]) -> Result<(), impl CommandError>;
 
     /// Resume the player.
-    fn resume(&self) -> Result<(), impl Error>;
+    fn resume(&self) -> Result<(), impl CommandError>;
 
     /// Set the loop parameter of the player.
-    fn set_loop(&self, enabled: bool) -> Result<(), impl Error>;
+    fn set_loop(&self, enabled: bool) -> Result<(), impl CommandError>;
 
     /// Set the volume level of the player.
-    fn set_volume(&self, value: u8) -> Result<(), impl Error>;
+    fn set_volume(&self, value: u8) -> Result<(), impl CommandError>;
 
     /// Play the next track.
-    fn skip(&self) -> Result<(), impl Error>;
+    fn skip(&self) -> Result<(), impl CommandError>;
+
+    /// Check if the player supports the given operation.
+    fn supports(&self, op: PlayerOp) -> bool;
+
+    /// Check if the session role allows the given operation.
+    fn allows(&self, op: PlayerOp) -> bool;
+
+    /// Check if the given operation is both supported and allowed.
+    fn available(&self, op: PlayerOp) -> bool {
+        self.supports(op) && self.allows(op)
+    }
+}
+
+/// Error of a command that could not be sent to the player.
+pub trait CommandError: Error {
+    /// Get the reason of the error, as shown to the user.
+    fn kind(&self) -> CommandErrorKind;
+}
+
+/// Reason why a command could not be sent to the player.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommandErrorKind {
+    NotConnected,
+    Unsupported,
+    Forbidden,
+    Internal,
+}
+
+/// Operations that may be supported by the player.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PlayerOp {
+    Batch,
+    Clear,
+    Enqueue,
+    Loop,
+    Move,
+    Pause,
+    Prev,
+    Remove,
+    RemoveWhere,
+    Restore,
+    Resume,
+    Skip,
+    Volume,
+}
+
+/// Role of the remote session, limiting the allowed operations.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionRole {
+    Viewer,
+    Dj,
+    #[default]
+    Admin,
+}
+
+impl SessionRole {
+    /// Check if the role allows the given operation.
+    pub fn allows(self, op: PlayerOp) -> bool {
+        match self {
+            SessionRole::Viewer => false,
+            SessionRole::Dj => !matches!(
+                op,
+                PlayerOp::Batch | PlayerOp::Clear | PlayerOp::RemoveWhere
+            ),
+            SessionRole::Admin => true,
+        }
+    }
+}
+
+impl fmt::Display for SessionRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SessionRole::Viewer => "viewer",
+            SessionRole::Dj => "dj",
+            SessionRole::Admin => "admin",
+        })
+    }
+}
+
+impl FromStr for SessionRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(SessionRole::Viewer),
+            "dj" => Ok(SessionRole::Dj),
+            "admin" => Ok(SessionRole::Admin),
+            _ => Err(format!("unknown role: {s}")),
+        }
+    }
+}
+
+/// Action applied to a batch of tracks in the queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QueueAction {
+    Remove,
+    MoveToTop,
+    MoveToBottom,
+}
+
+/// Criteria for removing multiple tracks from the queue.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RemoveFilter {
+    Uploader(String),
+    Requester(String),
+}
+
+impl RemoveFilter {
+    /// Check if the track matches the filter.
+    pub fn matches(&self, track: &impl TrackSnapshot) -> bool {
+        match self {
+            RemoveFilter::Uploader(name) => track.uploader() == name,
+            RemoveFilter::Requester(id) => track.requester().is_some_and(|r| r.id == id),
+        }
+    }
 }
 
 pub trait PlayerSnapshot<T: TrackSnapshot>: Default {
@@ -43,12 +175,18 @@ pub trait PlayerSnapshot<T: TrackSnapshot>: Default {
     /// Get the current state of the player.
     fn state(&self) -> MusicPlayerState;
 
+    /// Get the playback position within the current track.
+    fn position(&self) -> Duration;
+
     /// Get the contents of the queue.
     fn queue(&self) -> &[T];
+
+    /// Get the track that is currently playing, if any.
+    fn current(&self) -> Option<&T>;
 }
 
 /// State set for the music player.
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MusicPlayerState {
     Idle,
     Playing,
@@ -70,6 +208,9 @@ pub trait TrackSnapshot: Clone + Send + Sync {
     /// Get the duration of the track.
     fn duration(&self) -> Duration;
 
+    /// Get the duration of the track, as formatted by the extractor.
+    fn duration_string(&self) -> &str;
+
     /// Get the track URL.
     fn webpage_url(&self) -> &str;
 
@@ -78,4 +219,17 @@ pub trait TrackSnapshot: Clone + Send + Sync {
 
     /// Get the track thumbnail URL as an optional string.
     fn thumbnail(&self) -> Option<&str>;
+
+    /// Get the name of the extractor that resolved the track.
+    fn extractor(&self) -> &str;
+
+    /// Get the user that requested the track, if known.
+    fn requester(&self) -> Option<TrackRequester<'_>>;
+}
+
+/// User that requested a track.
+pub struct TrackRequester<'a> {
+    pub id: &'a str,
+    pub display_name: &'a str,
+    pub avatar_url: Option<&'a str>,
 }
@@ -0,0 +1,505 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use thiserror::Error;
+
+use crate::player::TrackSnapshot;
+
+/// File format of an exported playlist.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaylistFormat {
+    M3u,
+    Xspf,
+    Json,
+}
+
+#[derive(Error, Debug)]
+pub enum PlaylistError {
+    #[error("cannot serialize playlist")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Line of an imported playlist that could not be read.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ImportError {
+    #[error("line {0}: not a web address")]
+    InvalidUrl(usize),
+    #[error("line {0}: unclosed track element")]
+    UnclosedTrack(usize),
+    #[error("line {0}: track without a location")]
+    MissingLocation(usize),
+    #[error("line {0}: not a list of tracks")]
+    InvalidJson(usize),
+}
+
+/// Track read from an imported playlist.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlaylistEntry {
+    pub line: usize,
+    pub url: String,
+    pub title: Option<String>,
+}
+
+/// Tracks read from an imported playlist, along with the lines that were skipped.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportedPlaylist {
+    pub entries: Vec<PlaylistEntry>,
+    pub errors: Vec<ImportError>,
+}
+
+/// Read an XSPF, JSON or extended M3U playlist, or a list of URLs, one per line.
+pub fn import(text: &str) -> ImportedPlaylist {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let start = text.trim_start();
+    if start.starts_with('<') {
+        from_xspf(text)
+    } else if start.starts_with('[') {
+        from_json(text)
+    } else {
+        from_lines(text)
+    }
+}
+
+impl PlaylistFormat {
+    pub const ALL: [PlaylistFormat; 3] = [
+        PlaylistFormat::M3u,
+        PlaylistFormat::Xspf,
+        PlaylistFormat::Json,
+    ];
+
+    /// Get the user-facing name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u => "M3U",
+            PlaylistFormat::Xspf => "XSPF",
+            PlaylistFormat::Json => "JSON",
+        }
+    }
+
+    /// Get the file name extension of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u => "m3u",
+            PlaylistFormat::Xspf => "xspf",
+            PlaylistFormat::Json => "json",
+        }
+    }
+
+    /// Serialize the tracks into a playlist file.
+    pub fn export<T: TrackSnapshot + Serialize>(
+        self,
+        tracks: &[T],
+    ) -> Result<String, PlaylistError> {
+        Ok(match self {
+            PlaylistFormat::M3u => to_m3u(tracks),
+            PlaylistFormat::Xspf => to_xspf(tracks),
+            PlaylistFormat::Json => serde_json::to_string_pretty(tracks)?,
+        })
+    }
+}
+
+/// Replace line breaks, which would end an M3U directive early.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn to_m3u(tracks: &[impl TrackSnapshot]) -> String {
+    let mut m3u = String::from("#EXTM3U\n");
+    for track in tracks {
+        let duration = track.duration().as_secs();
+        let _ = writeln!(m3u, "#EXTINF:{duration},{}", single_line(track.title()));
+        let _ = writeln!(m3u, "{}", single_line(track.webpage_url()));
+    }
+    m3u
+}
+
+fn to_xspf(tracks: &[impl TrackSnapshot]) -> String {
+    let mut xspf = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n",
+        "  <trackList>\n",
+    ));
+    for track in tracks {
+        xspf.push_str("    <track>\n");
+        let _ = writeln!(
+            xspf,
+            "      <location>{}</location>",
+            escape_xml(track.webpage_url())
+        );
+        let _ = writeln!(xspf, "      <title>{}</title>", escape_xml(track.title()));
+        let _ = writeln!(
+            xspf,
+            "      <creator>{}</creator>",
+            escape_xml(track.uploader())
+        );
+        let _ = writeln!(
+            xspf,
+            "      <duration>{}</duration>",
+            track.duration().as_millis()
+        );
+        if let Some(thumbnail) = track.thumbnail() {
+            let _ = writeln!(xspf, "      <image>{}</image>", escape_xml(thumbnail));
+        }
+        xspf.push_str("    </track>\n");
+    }
+    xspf.push_str("  </trackList>\n</playlist>\n");
+    xspf
+}
+
+fn is_url(text: &str) -> bool {
+    let scheme = text.starts_with("https://") || text.starts_with("http://");
+    scheme && !text.contains(char::is_whitespace)
+}
+
+/// Read an M3U playlist, or any other list of URLs with `#` comments.
+fn from_lines(text: &str) -> ImportedPlaylist {
+    let mut playlist = ImportedPlaylist::default();
+    let mut title = None;
+    for (line, content) in (1..).zip(text.lines()) {
+        let content = content.trim();
+        if let Some(info) = content.strip_prefix("#EXTINF:") {
+            title = info
+                .split_once(',')
+                .map(|(_, title)| title.trim().to_string())
+                .filter(|title| !title.is_empty());
+        } else if content.is_empty() || content.starts_with('#') {
+            continue;
+        } else if is_url(content) {
+            playlist.entries.push(PlaylistEntry {
+                line,
+                url: content.to_string(),
+                title: title.take(),
+            });
+        } else {
+            title = None;
+            playlist.errors.push(ImportError::InvalidUrl(line));
+        }
+    }
+    playlist
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                name => name
+                    .strip_prefix("#x")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match entity {
+            Some((c, end)) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Get the text content of the first element with the given name.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+    let len = xml[start..].find(&format!("</{name}>"))?;
+    Some(xml[start..start + len].trim())
+}
+
+/// Find the start of the next `track` element, skipping `trackList`.
+fn find_track(xml: &str) -> Option<usize> {
+    xml.match_indices("<track")
+        .map(|(idx, _)| idx)
+        .find(|idx| xml[idx + 6..].starts_with(|c: char| c == '>' || c.is_whitespace()))
+}
+
+fn from_xspf(text: &str) -> ImportedPlaylist {
+    // Tracks are found in order, so only count the lines since the previous one.
+    let (mut counted, mut lines) = (0, 1);
+    let mut line_at = |offset: usize| {
+        lines += text[counted..offset].matches('\n').count();
+        counted = offset;
+        lines
+    };
+    let mut playlist = ImportedPlaylist::default();
+    let mut rest = 0;
+    while let Some(found) = find_track(&text[rest..]) {
+        let start = rest + found;
+        let line = line_at(start);
+        let Some(len) = text[start..].find("</track>") else {
+            playlist.errors.push(ImportError::UnclosedTrack(line));
+            break;
+        };
+        let track = &text[start..start + len];
+        rest = start + len + "</track>".len();
+        match element(track, "location").map(unescape_xml) {
+            Some(url) if is_url(&url) => playlist.entries.push(PlaylistEntry {
+                line,
+                url,
+                title: element(track, "title")
+                    .map(unescape_xml)
+                    .filter(|title| !title.is_empty()),
+            }),
+            Some(_) => playlist.errors.push(ImportError::InvalidUrl(line)),
+            None => playlist.errors.push(ImportError::MissingLocation(line)),
+        }
+    }
+    playlist
+}
+
+/// Track of a JSON playlist, as exported from the queue.
+#[derive(Deserialize)]
+struct JsonTrack {
+    webpage_url: Option<String>,
+    title: Option<String>,
+}
+
+/// Read a JSON array of queue entries, as written by [`PlaylistFormat::Json`].
+fn from_json(text: &str) -> ImportedPlaylist {
+    let mut playlist = ImportedPlaylist::default();
+    let tracks: Vec<&RawValue> = match serde_json::from_str(text) {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            playlist.errors.push(ImportError::InvalidJson(e.line()));
+            return playlist;
+        }
+    };
+    for track in tracks {
+        // The raw values borrow from the text, so their offset gives the line of the track.
+        let offset = track.get().as_ptr() as usize - text.as_ptr() as usize;
+        let line = text[..offset].matches('\n').count() + 1;
+        match serde_json::from_str::<JsonTrack>(track.get()) {
+            Ok(JsonTrack {
+                webpage_url: Some(url),
+                title,
+            }) if is_url(&url) => playlist.entries.push(PlaylistEntry {
+                line,
+                url,
+                title: title.filter(|title| !title.is_empty()),
+            }),
+            Ok(JsonTrack {
+                webpage_url: Some(_),
+                ..
+            }) => playlist.errors.push(ImportError::InvalidUrl(line)),
+            Ok(_) => playlist.errors.push(ImportError::MissingLocation(line)),
+            Err(_) => playlist.errors.push(ImportError::InvalidJson(line)),
+        }
+    }
+    playlist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_api::{queue_entry, QueueEntry};
+
+    fn tracks() -> Vec<QueueEntry> {
+        vec![
+            queue_entry("a", "First", "https://example.com/a"),
+            queue_entry("b", "Second", "https://example.com/b?list=1&index=2"),
+        ]
+    }
+
+    fn read_back(format: PlaylistFormat, tracks: &[QueueEntry]) -> Vec<(String, Option<String>)> {
+        let playlist = import(&format.export(tracks).unwrap());
+        assert_eq!(playlist.errors, vec![]);
+        let entries = playlist.entries.into_iter();
+        entries.map(|e| (e.url, e.title)).collect()
+    }
+
+    fn urls_and_titles(tracks: &[QueueEntry]) -> Vec<(String, Option<String>)> {
+        let tracks = tracks.iter();
+        tracks
+            .map(|t| (t.webpage_url().to_string(), Some(t.title().to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn m3u_round_trip() {
+        let tracks = tracks();
+        assert_eq!(
+            read_back(PlaylistFormat::M3u, &tracks),
+            urls_and_titles(&tracks)
+        );
+    }
+
+    #[test]
+    fn xspf_round_trip() {
+        let tracks = tracks();
+        assert_eq!(
+            read_back(PlaylistFormat::Xspf, &tracks),
+            urls_and_titles(&tracks)
+        );
+    }
+
+    #[test]
+    fn xspf_escapes_special_characters() {
+        let tracks = vec![queue_entry(
+            "a",
+            r#"Rock & Roll <"Live"> 'n' more"#,
+            "https://example.com/a?b=1&c=2",
+        )];
+        let xspf = PlaylistFormat::Xspf.export(&tracks).unwrap();
+        assert!(xspf.contains("Rock &amp; Roll &lt;&quot;Live&quot;&gt; &apos;n&apos; more"));
+        assert_eq!(
+            read_back(PlaylistFormat::Xspf, &tracks),
+            urls_and_titles(&tracks)
+        );
+    }
+
+    #[test]
+    fn json_import_round_trip() {
+        let tracks = tracks();
+        assert_eq!(
+            read_back(PlaylistFormat::Json, &tracks),
+            urls_and_titles(&tracks)
+        );
+    }
+
+    #[test]
+    fn json_reports_errors_with_line() {
+        let json = concat!(
+            "[\n",
+            "  { \"webpage_url\": \"https://example.com/a\", \"title\": \"First\" },\n",
+            "  { \"webpage_url\": \"not a url\" },\n",
+            "  { \"title\": \"No location\" },\n",
+            "  \"https://example.com/b\"\n",
+            "]\n",
+        );
+        let playlist = import(json);
+        let lines: Vec<_> = playlist.entries.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![2]);
+        let errors = vec![
+            ImportError::InvalidUrl(3),
+            ImportError::MissingLocation(4),
+            ImportError::InvalidJson(5),
+        ];
+        assert_eq!(playlist.errors, errors);
+        let unclosed = import("[\n  { \"webpage_url\": \"https://example.com/a\" }\n");
+        assert_eq!(unclosed.errors, vec![ImportError::InvalidJson(3)]);
+    }
+
+    #[test]
+    fn extinf_title_attaches_to_next_url() {
+        let m3u = "#EXTM3U\n#EXTINF:213,First\n\nhttps://example.com/a\nhttps://example.com/b\n";
+        let playlist = import(m3u);
+        let titles: Vec<_> = playlist
+            .entries
+            .iter()
+            .map(|e| e.title.as_deref())
+            .collect();
+        assert_eq!(titles, vec![Some("First"), None]);
+    }
+
+    #[test]
+    fn extinf_title_is_cleared_after_invalid_line() {
+        let m3u = "#EXTINF:213,First\nnot a url\nhttps://example.com/a\n";
+        let playlist = import(m3u);
+        assert_eq!(playlist.errors, vec![ImportError::InvalidUrl(2)]);
+        assert_eq!(
+            playlist.entries,
+            vec![PlaylistEntry {
+                line: 3,
+                url: "https://example.com/a".to_string(),
+                title: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn url_list_reports_invalid_lines() {
+        let list =
+            "https://example.com/a\n# comment\nftp://example.com/b\nhttps://example.com/c d\n";
+        let playlist = import(list);
+        assert_eq!(playlist.entries.len(), 1);
+        let errors = vec![ImportError::InvalidUrl(3), ImportError::InvalidUrl(4)];
+        assert_eq!(playlist.errors, errors);
+    }
+
+    #[test]
+    fn xspf_reports_errors_with_line() {
+        let xspf = concat!(
+            "<playlist>\n",
+            "  <trackList>\n",
+            "    <track><location>https://example.com/a</location></track>\n",
+            "    <track>\n",
+            "      <location>not a url</location>\n",
+            "    </track>\n",
+            "    <track><title>No location</title></track>\n",
+            "    <track><location>https://example.com/b</location>\n",
+            "  </trackList>\n",
+            "</playlist>\n",
+        );
+        let playlist = import(xspf);
+        let lines: Vec<_> = playlist.entries.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3]);
+        let errors = vec![
+            ImportError::InvalidUrl(4),
+            ImportError::MissingLocation(7),
+            ImportError::UnclosedTrack(8),
+        ];
+        assert_eq!(playlist.errors, errors);
+    }
+
+    #[test]
+    fn import_strips_byte_order_mark() {
+        let xspf = "\u{feff}<playlist><trackList><track><location>https://example.com/a</location></track></trackList></playlist>";
+        assert_eq!(import(xspf).entries.len(), 1);
+        let m3u = "\u{feff}https://example.com/a\n";
+        let playlist = import(m3u);
+        assert_eq!(playlist.entries.len(), 1);
+        assert_eq!(playlist.errors, vec![]);
+    }
+
+    #[test]
+    fn unescape_numeric_entities() {
+        assert_eq!(unescape_xml("Caf&#233; &#x2013; &#X41;"), "Café – &#X41;");
+        assert_eq!(unescape_xml("&#x1F3B5;&amp;&#38;"), "\u{1F3B5}&&");
+        assert_eq!(unescape_xml("&#xD800; & &unknown;"), "&#xD800; & &unknown;");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let tracks = tracks();
+        let exported = PlaylistFormat::Json.export(&tracks).unwrap();
+        let imported: Vec<QueueEntry> = serde_json::from_str(&exported).unwrap();
+        assert_eq!(
+            serde_json::to_value(imported).unwrap(),
+            serde_json::to_value(tracks).unwrap()
+        );
+    }
+}
@@ -0,0 +1,333 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use thiserror::Error;
+
+use crate::remote_api::{RemoteCommandModel, RemoteMessageModel};
+
+/// Codecs supported by the client, in order of preference.
+pub const CODECS: [&dyn WireCodec; 2] = [&CborCodec, &JsonCodec];
+
+/// WebSocket message frame.
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Error, Debug)]
+pub enum CodecError {
+    #[error("JSON error")]
+    Json(#[from] serde_json::Error),
+    #[error("CBOR encode error")]
+    CborEncode(#[from] ciborium::ser::Error<std::io::Error>),
+    #[error("CBOR decode error")]
+    CborDecode(#[from] ciborium::de::Error<std::io::Error>),
+    #[error("unexpected frame type")]
+    FrameType,
+}
+
+pub trait WireCodec: Send + Sync {
+    /// Get the WebSocket subprotocol that selects this codec.
+    fn protocol(&self) -> &'static str;
+
+    /// Encode a command sent to the server.
+    fn encode(&self, cmd: &RemoteCommandModel) -> Result<Frame, CodecError>;
+
+    /// Decode a message received from the server.
+    fn decode(&self, frame: &Frame) -> Result<RemoteMessageModel, CodecError>;
+}
+
+/// Get the codec selected by the given WebSocket subprotocol.
+pub fn for_protocol(protocol: &str) -> &'static dyn WireCodec {
+    CODECS
+        .into_iter()
+        .find(|c| c.protocol() == protocol)
+        .unwrap_or(&JsonCodec)
+}
+
+/// Text codec using JSON, supported by all servers.
+pub struct JsonCodec;
+
+impl WireCodec for JsonCodec {
+    fn protocol(&self) -> &'static str {
+        "acme-bot"
+    }
+
+    fn encode(&self, cmd: &RemoteCommandModel) -> Result<Frame, CodecError> {
+        Ok(Frame::Text(serde_json::to_string(cmd)?))
+    }
+
+    fn decode(&self, frame: &Frame) -> Result<RemoteMessageModel, CodecError> {
+        match frame {
+            Frame::Text(text) => Ok(serde_json::from_str(text)?),
+            Frame::Binary(_) => Err(CodecError::FrameType),
+        }
+    }
+}
+
+/// Binary codec using CBOR (RFC 8949).
+pub struct CborCodec;
+
+impl WireCodec for CborCodec {
+    fn protocol(&self) -> &'static str {
+        "acme-bot.cbor"
+    }
+
+    fn encode(&self, cmd: &RemoteCommandModel) -> Result<Frame, CodecError> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(cmd, &mut buf)?;
+        Ok(Frame::Binary(buf))
+    }
+
+    fn decode(&self, frame: &Frame) -> Result<RemoteMessageModel, CodecError> {
+        match frame {
+            Frame::Binary(bytes) => Ok(ciborium::from_reader(bytes.as_slice())?),
+            Frame::Text(_) => Err(CodecError::FrameType),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::discriminant;
+
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::remote_api::*;
+
+    /// Get the names of the schema types that make up the given root model.
+    fn schema_types(model: &str) -> Vec<String> {
+        let schema: Value = serde_json::from_str(include_str!("schema.json")).unwrap();
+        let variants = schema["$defs"][model]["oneOf"].as_array().unwrap();
+        let refs = variants.iter().map(|v| v["$ref"].as_str().unwrap());
+        refs.map(|r| r.trim_start_matches("#/$defs/").to_string())
+            .collect()
+    }
+
+    /// Get the schema type of the command, so that every variant needs a sample.
+    fn command_type(cmd: &RemoteCommandModel) -> &'static str {
+        match cmd {
+            RemoteCommandModel::BatchCommand(_) => "BatchCommand",
+            RemoteCommandModel::ClearCommand(_) => "ClearCommand",
+            RemoteCommandModel::DeltaCommand(_) => "DeltaCommand",
+            RemoteCommandModel::EnqueueCommand(_) => "EnqueueCommand",
+            RemoteCommandModel::LoopCommand(_) => "LoopCommand",
+            RemoteCommandModel::MoveCommand(_) => "MoveCommand",
+            RemoteCommandModel::PairCommand(_) => "PairCommand",
+            RemoteCommandModel::PauseCommand(_) => "PauseCommand",
+            RemoteCommandModel::PrevCommand(_) => "PrevCommand",
+            RemoteCommandModel::RemoveCommand(_) => "RemoveCommand",
+            RemoteCommandModel::RemoveWhereCommand(_) => "RemoveWhereCommand",
+            RemoteCommandModel::RestoreQueueCommand(_) => "RestoreQueueCommand",
+            RemoteCommandModel::ResumeCommand(_) => "ResumeCommand",
+            RemoteCommandModel::ResyncCommand(_) => "ResyncCommand",
+            RemoteCommandModel::SkipCommand(_) => "SkipCommand",
+            RemoteCommandModel::VolumeCommand(_) => "VolumeCommand",
+        }
+    }
+
+    /// Get the schema type of the message, so that every variant needs a sample.
+    fn message_type(msg: &RemoteMessageModel) -> &'static str {
+        match msg {
+            RemoteMessageModel::DeltaModel(_) => "DeltaModel",
+            RemoteMessageModel::ErrorModel(_) => "ErrorModel",
+            RemoteMessageModel::HelloModel(_) => "HelloModel",
+            RemoteMessageModel::PairingModel(_) => "PairingModel",
+            RemoteMessageModel::PlayerModel(_) => "PlayerModel",
+        }
+    }
+
+    fn command_samples(name: &str) -> Vec<RemoteCommandModel> {
+        match name {
+            "BatchCommand" => {
+                vec![command::<BatchCommand>(
+                    json!({ "op": "batch", "code": 1, "action": "move_top", "ids": ["a", "b"] }),
+                )]
+            }
+            "ClearCommand" => vec![command::<ClearCommand>(json!({ "op": "clear", "code": 1 }))],
+            "DeltaCommand" => vec![command::<DeltaCommand>(
+                json!({ "op": "delta", "code": 1, "enabled": true }),
+            )],
+            "EnqueueCommand" => {
+                vec![command::<EnqueueCommand>(
+                    json!({ "op": "enqueue", "code": 1, "urls": ["https://example.com/a"] }),
+                )]
+            }
+            "LoopCommand" => vec![command::<LoopCommand>(
+                json!({ "op": "loop", "code": 1, "enabled": false }),
+            )],
+            "MoveCommand" => vec![command::<MoveCommand>(
+                json!({ "op": "move", "code": 1, "offset": 3, "id": "a" }),
+            )],
+            "PairCommand" => vec![command::<PairCommand>(json!({ "op": "pair", "code": 1 }))],
+            "PauseCommand" => vec![command::<PauseCommand>(json!({ "op": "pause", "code": 1 }))],
+            "PrevCommand" => vec![command::<PrevCommand>(json!({ "op": "prev", "code": 1 }))],
+            "RemoveCommand" => vec![command::<RemoveCommand>(
+                json!({ "op": "remove", "code": 1, "offset": 0, "id": "a" }),
+            )],
+            "RemoveWhereCommand" => vec![command::<RemoveWhereCommand>(json!({
+                "op": "remove_where",
+                "code": 1,
+                "uploader": null,
+                "requester_id": "1234"
+            }))],
+            "RestoreQueueCommand" => vec![command::<RestoreQueueCommand>(json!({
+                "op": "restore_queue",
+                "code": 1,
+                "entries": [{ "offset": 2, "id": "a", "webpage_url": "https://example.com/a" }]
+            }))],
+            "ResumeCommand" => vec![command::<ResumeCommand>(
+                json!({ "op": "resume", "code": 1 }),
+            )],
+            "ResyncCommand" => vec![command::<ResyncCommand>(
+                json!({ "op": "resync", "code": 1 }),
+            )],
+            "SkipCommand" => vec![command::<SkipCommand>(json!({ "op": "skip", "code": 1 }))],
+            "VolumeCommand" => vec![command::<VolumeCommand>(
+                json!({ "op": "volume", "code": 1, "value": 75 }),
+            )],
+            _ => panic!("no sample for command {name}"),
+        }
+    }
+
+    fn message_samples(name: &str) -> Vec<Value> {
+        match name {
+            "DeltaModel" => vec![json!({
+                "op": "delta",
+                "seq": 8,
+                "patch": [{ "op": "replace", "path": "/volume", "value": 50 }]
+            })],
+            "ErrorModel" => vec![
+                json!({ "op": "error", "message": "queue is empty", "command": "skip" }),
+                json!({ "op": "error", "message": "internal error", "command": null }),
+            ],
+            "HelloModel" => vec![
+                json!({ "op": "hello", "version": 1, "ops": ["delta", "pair"], "role": "dj" }),
+                json!({ "op": "hello", "version": 1, "ops": [], "role": null }),
+            ],
+            "PairingModel" => vec![json!({ "op": "pairing", "token": "abcd", "ttl": 300 })],
+            "PlayerModel" => vec![
+                json!({
+                    "loop": true,
+                    "volume": 100,
+                    "position": 12345,
+                    "state": "playing",
+                    "queue": [
+                        queue_entry_json("a", "First", "https://example.com/a", json!(213)),
+                        queue_entry_json("b", "Second", "https://example.com/b", json!(213.5)),
+                    ],
+                    "current": queue_entry_json("c", "Third", "https://example.com/c", json!(0.25)),
+                    "seq": 7
+                }),
+                json!({
+                    "loop": false,
+                    "volume": 0,
+                    "position": 0,
+                    "state": "idle",
+                    "queue": [],
+                    "current": null,
+                    "seq": null
+                }),
+            ],
+            _ => panic!("no sample for message {name}"),
+        }
+    }
+
+    /// Build a command of the given type, the untagged model cannot tell apart
+    /// commands with the same fields.
+    fn command<T: DeserializeOwned + Into<RemoteCommandModel>>(cmd: Value) -> RemoteCommandModel {
+        serde_json::from_value::<T>(cmd).unwrap().into()
+    }
+
+    fn commands() -> Vec<RemoteCommandModel> {
+        let mut commands = vec![];
+        for name in schema_types("RemoteCommandModel") {
+            for cmd in command_samples(&name) {
+                assert_eq!(command_type(&cmd), name);
+                commands.push(cmd);
+            }
+        }
+        commands
+    }
+
+    fn messages() -> Vec<RemoteMessageModel> {
+        let mut messages = vec![];
+        for name in schema_types("RemoteMessageModel") {
+            for sample in message_samples(&name) {
+                let msg: RemoteMessageModel = serde_json::from_value(sample).unwrap();
+                assert_eq!(message_type(&msg), name);
+                messages.push(msg);
+            }
+        }
+        messages
+    }
+
+    fn assert_same<T: Serialize + std::fmt::Debug>(expected: &T, actual: &T) {
+        assert_eq!(discriminant(expected), discriminant(actual), "{actual:?}");
+        let expected = serde_json::to_value(expected).unwrap();
+        assert_eq!(expected, serde_json::to_value(actual).unwrap());
+    }
+
+    /// Decode a command the way the server sees it, before looking up its type by `op`.
+    fn server_decode(frame: &Frame) -> Value {
+        match frame {
+            Frame::Text(text) => serde_json::from_str(text).unwrap(),
+            Frame::Binary(bytes) => ciborium::from_reader(bytes.as_slice()).unwrap(),
+        }
+    }
+
+    /// Encode a message the way the server does.
+    fn server_encode(codec: &dyn WireCodec, msg: &RemoteMessageModel) -> Frame {
+        if codec.protocol() == JsonCodec.protocol() {
+            Frame::Text(serde_json::to_string(msg).unwrap())
+        } else {
+            let mut buf = Vec::new();
+            ciborium::into_writer(msg, &mut buf).unwrap();
+            Frame::Binary(buf)
+        }
+    }
+
+    #[test]
+    fn commands_round_trip() {
+        for codec in CODECS {
+            for cmd in commands() {
+                let frame = codec.encode(&cmd).unwrap();
+                let expected = serde_json::to_value(&cmd).unwrap();
+                assert_eq!(expected, server_decode(&frame));
+            }
+        }
+    }
+
+    #[test]
+    fn messages_round_trip() {
+        for codec in CODECS {
+            for msg in messages() {
+                let frame = server_encode(codec, &msg);
+                assert_same(&msg, &codec.decode(&frame).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn decode_rejects_other_frame_type() {
+        let text = Frame::Text("{}".to_string());
+        let binary = Frame::Binary(vec![]);
+        assert!(matches!(
+            JsonCodec.decode(&binary),
+            Err(CodecError::FrameType)
+        ));
+        assert!(matches!(
+            CborCodec.decode(&text),
+            Err(CodecError::FrameType)
+        ));
+    }
+
+    #[test]
+    fn unknown_protocol_uses_json() {
+        assert_eq!(for_protocol("acme-bot.cbor").protocol(), "acme-bot.cbor");
+        assert_eq!(for_protocol("").protocol(), "acme-bot");
+    }
+}
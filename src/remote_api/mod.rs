@@ -1,132 +1,488 @@
 // Copyright (C) 2024-2025  Krzysztof Molski
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use std::error::Error;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 
 use codee::string::FromToStringCodec;
-use leptos::prelude::Signal;
-use leptos_use::{use_websocket_with_options, UseWebSocketOptions, UseWebSocketReturn};
-use serde::Serialize;
+use leptos::ev;
+use leptos::logging;
+use leptos::prelude::*;
+use leptos::web_sys::{CloseEvent, WebSocket};
+use leptos_use::core::ConnectionReadyState;
+use leptos_use::{
+    use_event_listener, use_throttle_fn, use_websocket_with_options, use_window,
+    UseWebSocketOptions, UseWebSocketReturn,
+};
 use thiserror::Error;
 use typify::import_types;
 
-use crate::player::{MusicPlayerState, Player, PlayerSnapshot, TrackSnapshot};
+use crate::player::{
+    self, CommandError, CommandErrorKind, MusicPlayerState, Player, PlayerOp, PlayerSnapshot,
+    QueueAction, RemoveFilter, TrackRequester, TrackSnapshot,
+};
+use crate::remote_api::codec::{CodecError, Frame, WireCodec, CODECS};
+
+mod codec;
 
 import_types!("src/remote_api/schema.json");
 
+/// Operations supported by servers that do not send a hello message.
+const BASE_OPS: [&str; 9] = [
+    "clear", "loop", "move", "pause", "prev", "remove", "resume", "skip", "volume",
+];
+
+/// Minimum time between resync requests, in milliseconds.
+const RESYNC_INTERVAL_MS: f64 = 2000.0;
+
+/// WebSocket close code sent by the server when the bearer token is invalid or expired.
+const CLOSE_UNAUTHORIZED: u16 = 4001;
+
+type Socket = Signal<Option<WebSocket>, LocalStorage>;
+
 #[derive(Clone)]
 pub struct RemotePlayer {
-    pub(crate) state: Signal<Option<String>>,
-    send: Arc<dyn Fn(&String) + Send + Sync + 'static>,
+    pub(crate) state: Signal<PlayerModel>,
+    pub(crate) error: Signal<Option<ConnectionError>>,
+    pub(crate) pairing: Signal<Option<PairingModel>>,
+    pub(crate) server_error: Signal<Option<ErrorModel>>,
+    hello: Signal<Option<HelloModel>>,
+    ws: Arc<OnceLock<Socket>>,
     access_code: i64,
+    role: player::SessionRole,
+}
+
+/// Error reported by the server when closing the connection.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ConnectionError {
+    #[error("The session link is invalid or has expired.")]
+    Unauthorized,
 }
 
 #[derive(Error, Debug)]
 enum RemotePlayerError {
+    #[error("codec error")]
+    CodecError(#[from] CodecError),
     #[error("serialize error")]
     SerializeError(#[from] serde_json::Error),
+    #[error("operation not supported by server: {0}")]
+    UnsupportedOp(&'static str),
+    #[error("operation not allowed for role {0:?}: {1}")]
+    ForbiddenOp(player::SessionRole, &'static str),
+    #[error("not connected")]
+    NotConnected,
+    #[error("no snapshot to apply delta to")]
+    NoSnapshot,
+    #[error("missed delta: expected seq {expected}, got {actual}")]
+    SequenceGap { expected: i64, actual: i64 },
+    #[error("patch error")]
+    PatchError(#[from] json_patch::PatchError),
+}
+
+impl CommandError for RemotePlayerError {
+    fn kind(&self) -> CommandErrorKind {
+        match self {
+            RemotePlayerError::NotConnected => CommandErrorKind::NotConnected,
+            RemotePlayerError::UnsupportedOp(_) => CommandErrorKind::Unsupported,
+            RemotePlayerError::ForbiddenOp(..) => CommandErrorKind::Forbidden,
+            _ => CommandErrorKind::Internal,
+        }
+    }
+}
+
+/// Local copy of the player snapshot, kept up to date with deltas.
+#[derive(Default)]
+struct SnapshotDoc {
+    seq: Option<i64>,
+    doc: serde_json::Value,
+}
+
+impl SnapshotDoc {
+    fn reset(&mut self, snapshot: &PlayerModel) -> Result<(), RemotePlayerError> {
+        self.doc = serde_json::to_value(snapshot)?;
+        self.seq = snapshot.seq;
+        Ok(())
+    }
+
+    fn apply(&mut self, delta: DeltaModel) -> Result<PlayerModel, RemotePlayerError> {
+        let seq = self.seq.ok_or(RemotePlayerError::NoSnapshot)?;
+        if delta.seq != seq + 1 {
+            self.seq = None;
+            return Err(RemotePlayerError::SequenceGap {
+                expected: seq + 1,
+                actual: delta.seq,
+            });
+        }
+        let ops = delta.patch.into_iter().map(serde_json::Value::Object);
+        let patch: json_patch::Patch = serde_json::from_value(ops.collect())?;
+        if let Err(e) = json_patch::patch(&mut self.doc, &patch) {
+            self.seq = None;
+            return Err(e.into());
+        }
+        self.seq = Some(delta.seq);
+        Ok(serde_json::from_value(self.doc.clone())?)
+    }
 }
 
 impl RemotePlayer {
-    pub fn new(url: &str, token: &str, access_code: i64) -> Self {
-        let options = UseWebSocketOptions::default().protocols(Some(vec![
-            "acme-bot".to_string(),
-            format!("acme-bot.bearer.{token}"),
-        ]));
-        let UseWebSocketReturn { message, send, .. } =
-            use_websocket_with_options::<String, String, FromToStringCodec, _, _>(url, options);
-        Self {
-            send: Arc::new(send),
-            state: message,
+    pub fn new(url: &str, token: &str, access_code: i64, role: player::SessionRole) -> Self {
+        let state = RwSignal::new(PlayerModel::default());
+        let hello = RwSignal::new(None);
+        let error = RwSignal::new(None);
+        let pairing = RwSignal::new(None);
+        let server_error = RwSignal::new(None);
+        let doc = Arc::new(Mutex::new(SnapshotDoc::default()));
+        let ws = Arc::new(OnceLock::new());
+        let player = Self {
+            state: state.into(),
+            error: error.into(),
+            pairing: pairing.into(),
+            server_error: server_error.into(),
+            hello: hello.into(),
+            ws: ws.clone(),
             access_code,
+            role,
+        };
+        let resync = use_throttle_fn(
+            {
+                let player = player.clone();
+                move || player.resync()
+            },
+            RESYNC_INTERVAL_MS,
+        );
+        let on_frame = {
+            let player = player.clone();
+            move |frame: Frame| match player.codec().decode(&frame) {
+                Ok(RemoteMessageModel::HelloModel(h)) => {
+                    let deltas = h.ops.iter().any(|op| op == "delta");
+                    hello.set(Some(h));
+                    if deltas {
+                        player.enable_deltas();
+                    }
+                }
+                Ok(RemoteMessageModel::PlayerModel(s)) => {
+                    if let Err(e) = doc.lock().unwrap().reset(&s) {
+                        logging::error!("Invalid snapshot: {}", e);
+                    }
+                    state.set(s);
+                }
+                Ok(RemoteMessageModel::DeltaModel(d)) => {
+                    let result = doc.lock().unwrap().apply(d);
+                    match result {
+                        Ok(s) => state.set(s),
+                        Err(e) => {
+                            logging::warn!("Requesting resync: {}", e);
+                            resync();
+                        }
+                    }
+                }
+                Ok(RemoteMessageModel::PairingModel(p)) => {
+                    let token = p.token.clone();
+                    let ttl = std::time::Duration::from_secs(p.ttl.max(0) as u64);
+                    pairing.set(Some(p));
+                    set_timeout(
+                        move || {
+                            if pairing
+                                .with_untracked(|p| p.as_ref().is_some_and(|p| p.token == token))
+                            {
+                                pairing.set(None);
+                            }
+                        },
+                        ttl,
+                    );
+                }
+                Ok(RemoteMessageModel::ErrorModel(e)) => {
+                    logging::warn!("Command {:?} failed: {}", e.command, e.message);
+                    server_error.set(Some(e));
+                }
+                Err(e) => logging::error!("Invalid message: {}", e),
+            }
+        };
+        // The token will not become valid again, so stop reconnecting once it is rejected.
+        let close_ref = StoredValue::<Option<Arc<dyn Fn() + Send + Sync>>>::new(None);
+        let mut protocols: Vec<_> = CODECS.iter().map(|c| c.protocol().to_string()).collect();
+        protocols.push(format!("acme-bot.bearer.{token}"));
+        let options = UseWebSocketOptions::default()
+            .protocols(Some(protocols))
+            .on_message_raw({
+                let on_frame = on_frame.clone();
+                move |text: &str| on_frame(Frame::Text(text.to_string()))
+            })
+            .on_message_raw_bytes(Arc::new(move |bytes: &[u8]| {
+                on_frame(Frame::Binary(bytes.to_vec()))
+            }))
+            .on_open(move |_| error.set(None))
+            .on_close(move |e: CloseEvent| {
+                if e.code() == CLOSE_UNAUTHORIZED {
+                    error.set(Some(ConnectionError::Unauthorized));
+                    if let Some(close) = close_ref.get_value() {
+                        close();
+                    }
+                }
+            });
+        let UseWebSocketReturn {
+            ws: socket,
+            ready_state,
+            open,
+            close,
+            ..
+        } = use_websocket_with_options::<String, String, FromToStringCodec, _, _>(url, options);
+        close_ref.set_value(Some(Arc::new(close)));
+        // Reconnect attempts run out quickly while offline, so start over once back online.
+        let _ = use_event_listener(use_window(), ev::online, move |_| {
+            let closed = ready_state.get_untracked() == ConnectionReadyState::Closed;
+            let rejected = error.with_untracked(|e| e.is_some());
+            if closed && !rejected {
+                open();
+            }
+        });
+        let _ = ws.set(socket);
+        player
+    }
+
+    /// Get the session role, as confirmed by the server.
+    pub fn role(&self) -> player::SessionRole {
+        let role = self.hello.with(|h| h.as_ref().and_then(|h| h.role));
+        match role {
+            Some(SessionRole::Viewer) => player::SessionRole::Viewer,
+            Some(SessionRole::Dj) => player::SessionRole::Dj,
+            Some(SessionRole::Admin) => player::SessionRole::Admin,
+            None => self.role,
         }
     }
 
-    fn publish_json(&self, msg: impl Serialize) -> Result<(), RemotePlayerError> {
-        let msg = serde_json::to_string(&msg)?;
-        (*self.send)(&msg);
-        Ok(())
+    /// Get the codec negotiated with the server.
+    fn codec(&self) -> &'static dyn WireCodec {
+        let protocol = self.socket().map(|ws| ws.protocol()).unwrap_or_default();
+        codec::for_protocol(&protocol)
+    }
+
+    fn socket(&self) -> Option<WebSocket> {
+        self.ws.get().and_then(|ws| ws.get_untracked())
+    }
+
+    fn enable_deltas(&self) {
+        let cmd = DeltaCommand {
+            op: "delta".to_string(),
+            code: self.access_code,
+            enabled: true,
+        };
+        if let Err(e) = self.send(cmd.into()) {
+            logging::error!("Cannot enable deltas: {}", e);
+        }
+    }
+
+    fn resync(&self) {
+        let cmd = ResyncCommand {
+            op: "resync".to_string(),
+            code: self.access_code,
+        };
+        if let Err(e) = self.send(cmd.into()) {
+            logging::error!("Cannot request resync: {}", e);
+        }
+    }
+
+    /// Check if the server can issue short-lived pairing tokens.
+    pub fn supports_pairing(&self) -> bool {
+        self.hello.with(|h| {
+            h.as_ref()
+                .is_some_and(|h| h.ops.iter().any(|op| op == "pair"))
+        })
+    }
+
+    /// Request a short-lived pairing token, delivered through the `pairing` signal.
+    pub fn request_pairing(&self) -> Result<(), impl CommandError> {
+        if !self.supports_pairing() {
+            return Err(RemotePlayerError::UnsupportedOp("pair"));
+        }
+        let cmd = PairCommand {
+            op: "pair".to_string(),
+            code: self.access_code,
+        };
+        self.send(cmd.into())
+    }
+
+    fn publish(&self, op: PlayerOp, cmd: RemoteCommandModel) -> Result<(), RemotePlayerError> {
+        if !self.supports(op) {
+            return Err(RemotePlayerError::UnsupportedOp(op_name(op)));
+        }
+        if !self.allows(op) {
+            return Err(RemotePlayerError::ForbiddenOp(self.role(), op_name(op)));
+        }
+        self.send(cmd)
+    }
+
+    fn send(&self, cmd: RemoteCommandModel) -> Result<(), RemotePlayerError> {
+        let ws = self.socket().ok_or(RemotePlayerError::NotConnected)?;
+        let result = match self.codec().encode(&cmd)? {
+            Frame::Text(text) => ws.send_with_str(&text),
+            Frame::Binary(bytes) => ws.send_with_u8_array(&bytes),
+        };
+        result.map_err(|_| RemotePlayerError::NotConnected)
+    }
+}
+
+fn op_name(op: PlayerOp) -> &'static str {
+    match op {
+        PlayerOp::Batch => "batch",
+        PlayerOp::Clear => "clear",
+        PlayerOp::Enqueue => "enqueue",
+        PlayerOp::Loop => "loop",
+        PlayerOp::Move => "move",
+        PlayerOp::Pause => "pause",
+        PlayerOp::Prev => "prev",
+        PlayerOp::Remove => "remove",
+        PlayerOp::RemoveWhere => "remove_where",
+        PlayerOp::Restore => "restore_queue",
+        PlayerOp::Resume => "resume",
+        PlayerOp::Skip => "skip",
+        PlayerOp::Volume => "volume",
     }
 }
 
 impl Player for RemotePlayer {
-    fn clear(&self) -> Result<(), impl Error> {
+    fn batch(&self, action: QueueAction, ids: &[String]) -> Result<(), impl CommandError> {
+        let cmd = BatchCommand {
+            op: "batch".to_string(),
+            code: self.access_code,
+            action: match action {
+                QueueAction::Remove => BatchAction::Remove,
+                QueueAction::MoveToTop => BatchAction::MoveTop,
+                QueueAction::MoveToBottom => BatchAction::MoveBottom,
+            },
+            ids: ids.to_vec(),
+        };
+        self.publish(PlayerOp::Batch, cmd.into())
+    }
+
+    fn clear(&self) -> Result<(), impl CommandError> {
         let cmd = ClearCommand {
             op: "clear".to_string(),
             code: self.access_code,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Clear, cmd.into())
+    }
+
+    fn enqueue(&self, urls: &[String]) -> Result<(), impl CommandError> {
+        let cmd = EnqueueCommand {
+            op: "enqueue".to_string(),
+            code: self.access_code,
+            urls: urls.to_vec(),
+        };
+        self.publish(PlayerOp::Enqueue, cmd.into())
     }
 
-    fn move_to(&self, offset: usize, id: &str) -> Result<(), impl Error> {
+    fn move_to(&self, offset: usize, id: &str) -> Result<(), impl CommandError> {
         let cmd = MoveCommand {
             op: "move".to_string(),
             code: self.access_code,
             offset: offset as i64,
             id: id.to_string(),
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Move, cmd.into())
     }
 
-    fn pause(&self) -> Result<(), impl Error> {
+    fn pause(&self) -> Result<(), impl CommandError> {
         let cmd = PauseCommand {
             op: "pause".to_string(),
             code: self.access_code,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Pause, cmd.into())
     }
 
-    fn prev(&self) -> Result<(), impl Error> {
+    fn prev(&self) -> Result<(), impl CommandError> {
         let cmd = PrevCommand {
             op: "prev".to_string(),
             code: self.access_code,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Prev, cmd.into())
     }
 
-    fn remove(&self, offset: usize, id: &str) -> Result<(), impl Error> {
+    fn remove(&self, offset: usize, id: &str) -> Result<(), impl CommandError> {
         let cmd = RemoveCommand {
             op: "remove".to_string(),
             code: self.access_code,
             offset: offset as i64,
             id: id.to_string(),
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Remove, cmd.into())
+    }
+
+    fn remove_where(&self, filter: &RemoveFilter) -> Result<(), impl CommandError> {
+        let mut cmd = RemoveWhereCommand {
+            op: "remove_where".to_string(),
+            code: self.access_code,
+            uploader: None,
+            requester_id: None,
+        };
+        match filter {
+            RemoveFilter::Uploader(name) => cmd.uploader = Some(name.clone()),
+            RemoveFilter::Requester(id) => cmd.requester_id = Some(id.clone()),
+        }
+        self.publish(PlayerOp::RemoveWhere, cmd.into())
     }
 
-    fn resume(&self) -> Result<(), impl Error> {
+    fn restore<T: TrackSnapshot>(&self, tracks: &[(usize, T)]) -> Result<(), impl CommandError> {
+        let mut entries: Vec<_> = tracks
+            .iter()
+            .map(|(offset, track)| RestoreEntry {
+                offset: *offset as i64,
+                id: track.id().to_string(),
+                webpage_url: track.webpage_url().to_string(),
+            })
+            .collect();
+        entries.sort_by_key(|e| e.offset);
+        let cmd = RestoreQueueCommand {
+            op: "restore_queue".to_string(),
+            code: self.access_code,
+            entries,
+        };
+        self.publish(PlayerOp::Restore, cmd.into())
+    }
+
+    fn resume(&self) -> Result<(), impl CommandError> {
         let cmd = ResumeCommand {
             op: "resume".to_string(),
             code: self.access_code,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Resume, cmd.into())
     }
 
-    fn set_loop(&self, enabled: bool) -> Result<(), impl Error> {
+    fn set_loop(&self, enabled: bool) -> Result<(), impl CommandError> {
         let cmd = LoopCommand {
             op: "loop".to_string(),
             code: self.access_code,
             enabled,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Loop, cmd.into())
     }
 
-    fn set_volume(&self, value: u8) -> Result<(), impl Error> {
+    fn set_volume(&self, value: u8) -> Result<(), impl CommandError> {
         let cmd = VolumeCommand {
             op: "volume".to_string(),
             code: self.access_code,
             value: value as i64,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Volume, cmd.into())
     }
 
-    fn skip(&self) -> Result<(), impl Error> {
+    fn skip(&self) -> Result<(), impl CommandError> {
         let cmd = SkipCommand {
             op: "skip".to_string(),
             code: self.access_code,
         };
-        self.publish_json(cmd)
+        self.publish(PlayerOp::Skip, cmd.into())
+    }
+
+    fn allows(&self, op: PlayerOp) -> bool {
+        self.role().allows(op)
+    }
+
+    fn supports(&self, op: PlayerOp) -> bool {
+        let name = op_name(op);
+        self.hello.with(|h| match h {
+            Some(h) => h.ops.iter().any(|o| o == name),
+            None => BASE_OPS.contains(&name),
+        })
     }
 }
 
@@ -149,9 +505,17 @@ impl PlayerSnapshot<QueueEntry> for PlayerModel {
         }
     }
 
+    fn position(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.position.max(0) as u64)
+    }
+
     fn queue(&self) -> &[QueueEntry] {
         self.queue.as_slice()
     }
+
+    fn current(&self) -> Option<&QueueEntry> {
+        self.current.as_ref()
+    }
 }
 
 impl Default for PlayerModel {
@@ -163,6 +527,7 @@ impl Default for PlayerModel {
             state: PlayerState::Idle,
             queue: vec![],
             current: None,
+            seq: None,
         }
     }
 }
@@ -187,6 +552,10 @@ impl TrackSnapshot for QueueEntry {
         }
     }
 
+    fn duration_string(&self) -> &str {
+        &self.duration_string
+    }
+
     fn webpage_url(&self) -> &str {
         &self.webpage_url
     }
@@ -198,4 +567,111 @@ impl TrackSnapshot for QueueEntry {
     fn thumbnail(&self) -> Option<&str> {
         self.thumbnail.as_deref()
     }
+
+    fn extractor(&self) -> &str {
+        &self.extractor
+    }
+
+    fn requester(&self) -> Option<TrackRequester<'_>> {
+        self.requester.as_ref().map(|r| TrackRequester {
+            id: &r.id,
+            display_name: &r.display_name,
+            avatar_url: r.avatar_url.as_deref(),
+        })
+    }
+}
+
+/// Build a queue entry as the server would send it, for use in tests.
+#[cfg(test)]
+pub(crate) fn queue_entry_json(
+    id: &str,
+    title: &str,
+    url: &str,
+    duration: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "title": title,
+        "uploader": "Rick Astley",
+        "duration": duration,
+        "webpage_url": url,
+        "uploader_url": "https://www.youtube.com/@RickAstleyYT",
+        "duration_string": "3:33",
+        "thumbnail": format!("https://i.ytimg.com/vi/{id}/default.jpg"),
+        "extractor": "youtube",
+        "requester": {
+            "id": "1234",
+            "display_name": "kmolski",
+            "avatar_url": "https://cdn.discordapp.com/avatars/1234/abcd.png"
+        }
+    })
+}
+
+/// Build a queue entry for use in tests.
+#[cfg(test)]
+pub(crate) fn queue_entry(id: &str, title: &str, url: &str) -> QueueEntry {
+    let entry = queue_entry_json(id, title, url, serde_json::json!(213.5));
+    serde_json::from_value(entry).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn delta(seq: i64, patch: serde_json::Value) -> DeltaModel {
+        serde_json::from_value(json!({ "op": "delta", "seq": seq, "patch": patch })).unwrap()
+    }
+
+    fn doc_at(seq: i64) -> SnapshotDoc {
+        let mut doc = SnapshotDoc::default();
+        let snapshot = PlayerModel {
+            seq: Some(seq),
+            ..PlayerModel::default()
+        };
+        doc.reset(&snapshot).unwrap();
+        doc
+    }
+
+    #[test]
+    fn apply_next_delta() {
+        let mut doc = doc_at(1);
+        let patch = json!([{ "op": "replace", "path": "/volume", "value": 50 }]);
+        let snapshot = doc.apply(delta(2, patch)).unwrap();
+        assert_eq!(snapshot.volume, 50);
+        assert_eq!(doc.seq, Some(2));
+    }
+
+    #[test]
+    fn apply_without_snapshot() {
+        let mut doc = SnapshotDoc::default();
+        let result = doc.apply(delta(1, json!([])));
+        assert!(matches!(result, Err(RemotePlayerError::NoSnapshot)));
+    }
+
+    #[test]
+    fn apply_detects_gap() {
+        let mut doc = doc_at(1);
+        let result = doc.apply(delta(3, json!([])));
+        assert!(matches!(
+            result,
+            Err(RemotePlayerError::SequenceGap {
+                expected: 2,
+                actual: 3
+            })
+        ));
+        assert_eq!(doc.seq, None);
+        let result = doc.apply(delta(4, json!([])));
+        assert!(matches!(result, Err(RemotePlayerError::NoSnapshot)));
+    }
+
+    #[test]
+    fn apply_failed_patch_resets_seq() {
+        let mut doc = doc_at(1);
+        let patch = json!([{ "op": "remove", "path": "/missing" }]);
+        let result = doc.apply(delta(2, patch));
+        assert!(matches!(result, Err(RemotePlayerError::PatchError(_))));
+        assert_eq!(doc.seq, None);
+    }
 }
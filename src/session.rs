@@ -0,0 +1,169 @@
+// Copyright (C) 2025  Krzysztof Molski
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::fmt::Write;
+use std::num::ParseIntError;
+use std::string::FromUtf8Error;
+
+use base64::prelude::*;
+use leptos_router::location::{BrowserUrl, LocationProvider};
+use leptos_router::params::ParamsMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::player::SessionRole;
+
+/// Encode a query string value, as `application/x-www-form-urlencoded`.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+/// Key of the current session in `sessionStorage`.
+pub const SESSION_STORAGE_KEY: &str = "acme-bot-remote.session";
+
+/// Key of the saved sessions in `localStorage`.
+pub const SAVED_SESSIONS_KEY: &str = "acme-bot-remote.saved-sessions";
+
+/// Connection parameters of a remote session, as passed in the link from the bot.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SessionParams {
+    pub access_code: i64,
+    pub token: String,
+    pub server: String,
+    pub role: SessionRole,
+}
+
+/// Remote session saved under a user-chosen name.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SavedSession {
+    pub name: String,
+    pub params: SessionParams,
+}
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("missing parameter: {0}")]
+    MissingParam(&'static str),
+    #[error("invalid access code")]
+    InvalidAccessCode(#[from] ParseIntError),
+    #[error("invalid server address")]
+    InvalidServer(#[from] base64::DecodeError),
+    #[error("invalid server address")]
+    InvalidServerEncoding(#[from] FromUtf8Error),
+    #[error("invalid role: {0}")]
+    InvalidRole(String),
+    #[error("invalid link")]
+    InvalidLink,
+}
+
+impl SessionParams {
+    /// Parse the session parameters from the query string, if present.
+    pub fn from_query(query: &ParamsMap) -> Result<Option<Self>, SessionError> {
+        let Some(access_code) = query.get("ac") else {
+            return Ok(None);
+        };
+        let token = query.get("rt").ok_or(SessionError::MissingParam("rt"))?;
+        let server = query.get("ws").ok_or(SessionError::MissingParam("ws"))?;
+        let role = match query.get("role") {
+            Some(role) => role.parse().map_err(SessionError::InvalidRole)?,
+            None => SessionRole::default(),
+        };
+        Ok(Some(Self {
+            access_code: access_code.parse()?,
+            token,
+            server: String::from_utf8(BASE64_URL_SAFE_NO_PAD.decode(server)?)?,
+            role,
+        }))
+    }
+
+    /// Parse the session parameters from a link sent by the bot.
+    pub fn from_link(link: &str) -> Result<Self, SessionError> {
+        let url = BrowserUrl::parse(link.trim()).map_err(|_| SessionError::InvalidLink)?;
+        Self::from_query(url.search_params())?.ok_or(SessionError::MissingParam("ac"))
+    }
+
+    /// Build a link to the app at `base` that opens this session.
+    pub fn to_link(&self, base: &str) -> String {
+        let query = [
+            ("ac", self.access_code.to_string()),
+            ("rt", self.token.clone()),
+            ("ws", BASE64_URL_SAFE_NO_PAD.encode(&self.server)),
+            ("role", self.role.to_string()),
+        ];
+        let query: Vec<_> = query
+            .iter()
+            .map(|(name, value)| format!("{name}={}", encode_query_value(value)))
+            .collect();
+        format!("{base}?{}", query.join("&"))
+    }
+
+    /// Get the WebSocket URL of the session.
+    pub fn remote_url(&self) -> String {
+        format!("{}/{}", self.server, self.access_code)
+    }
+
+    /// Check if both parameters refer to the same bot and guild.
+    pub fn same_session(&self, other: &Self) -> bool {
+        self.server == other.server && self.access_code == other.access_code
+    }
+
+    /// Get the name of the session used when the user did not choose one.
+    pub fn default_name(&self) -> String {
+        let host = self
+            .server
+            .split_once("://")
+            .map_or(&*self.server, |(_, rest)| rest);
+        let host = host.split('/').next().unwrap_or(host);
+        format!("{host} #{}", self.access_code)
+    }
+}
+
+/// Save the session parameters, replacing the existing entry for the same session.
+///
+/// The entry keeps its name unless a new one is given.
+pub fn save_session(saved: &mut Vec<SavedSession>, params: SessionParams, name: Option<String>) {
+    match saved.iter_mut().find(|s| s.params.same_session(&params)) {
+        Some(entry) => {
+            if let Some(name) = name {
+                entry.name = name;
+            }
+            entry.params = params;
+        }
+        None => saved.push(SavedSession {
+            name: name.unwrap_or_else(|| params.default_name()),
+            params,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_link_encodes_query() {
+        let params = SessionParams {
+            access_code: 1234,
+            token: "a b&c=d/é".to_string(),
+            server: "wss://example.com".to_string(),
+            role: SessionRole::Dj,
+        };
+        assert_eq!(
+            params.to_link("https://remote.example.com/"),
+            "https://remote.example.com/?ac=1234&rt=a+b%26c%3Dd%2F%C3%A9\
+             &ws=d3NzOi8vZXhhbXBsZS5jb20&role=dj"
+        );
+    }
+}
@@ -1,19 +1,78 @@
 // Copyright (C) 2023-2025  Krzysztof Molski
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Arc;
 use std::time::Duration;
 
-use base64::prelude::*;
+use codee::string::{FromToStringCodec, JsonSerdeCodec};
+use leptos::ev::{self, MouseEvent};
+use leptos::html::{Dialog, Main};
 use leptos::logging;
 use leptos::prelude::*;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::wasm_bindgen::{JsCast, JsValue};
+use leptos::web_sys::js_sys::{Array, Date, Function, Reflect};
+use leptos::web_sys::{Blob, File, HtmlAnchorElement, HtmlInputElement, Url, VisibilityState};
 use leptos_router::hooks::use_query_map;
+use leptos_use::storage::{use_local_storage, use_session_storage};
+use leptos_use::{
+    signal_throttled, use_clipboard, use_document_visibility, use_element_size, use_event_listener,
+    use_scroll, use_service_worker_with_options, use_timestamp_with_options,
+    use_web_notification_with_options, use_window, use_window_focus, ShowOptions,
+    UseClipboardReturn, UseElementSizeReturn, UseScrollReturn, UseServiceWorkerOptions,
+    UseTimestampOptions, UseWebNotificationOptions, UseWebNotificationReturn,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::player::{MusicPlayerState, Player, PlayerSnapshot, TrackSnapshot};
-use crate::remote_api::{PlayerModel, RemotePlayer};
+use crate::i18n::{
+    format_duration, format_time, provide_locale, use_locale, use_messages, Locale, Messages,
+    LOCALE_KEY,
+};
+use crate::player::{
+    CommandError, CommandErrorKind, MusicPlayerState, Player, PlayerOp, PlayerSnapshot,
+    QueueAction, RemoveFilter, TrackSnapshot,
+};
+use crate::playlist::{self, ImportError, ImportedPlaylist, PlaylistFormat};
+use crate::remote_api::{ConnectionError, QueueEntry, RemotePlayer};
+use crate::session::{
+    save_session, SavedSession, SessionParams, SAVED_SESSIONS_KEY, SESSION_STORAGE_KEY,
+};
 
 const ICON_FRAME_SMALL: &str = "8 8 22 22";
 const ICON_FRAME_LARGE: &str = "0 0 38 38";
 
+/// Height of a single row in the queue, in pixels. Must match `$row-height` in index.scss.
+const ROW_HEIGHT: f64 = 48.0;
+
+/// Path of the service worker that caches the app shell.
+const SERVICE_WORKER_URL: &str = "./sw.js";
+
+/// Key of the track change notification setting in `localStorage`.
+const NOTIFICATIONS_KEY: &str = "acme-bot-remote.notifications";
+
+/// Key of the selected color theme in `localStorage`.
+const THEME_KEY: &str = "acme-bot-remote.theme";
+
+/// Minimum time between track change notifications, in milliseconds.
+const NOTIFICATION_INTERVAL_MS: f64 = 5000.0;
+
+/// Maximum number of error messages shown at once.
+const MAX_TOASTS: usize = 3;
+
+/// Time for which removed tracks can be put back into the queue.
+const UNDO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Width of the empty border around QR codes, in modules.
+const QR_QUIET_ZONE: usize = 4;
+
+/// Number of queued tracks shown in the now playing view.
+const UP_NEXT_COUNT: usize = 3;
+
+/// Number of rows mounted outside the visible part of the queue, on each side.
+const OVERSCAN_ROWS: usize = 8;
+
 const COPYRIGHT_INFO: &str = "\
 acme-bot-remote
 Copyright (C) 2023-2025  Krzysztof Molski
@@ -28,29 +87,289 @@ PARTICULAR PURPOSE.
 See the GNU Affero General Public License for more details.
 ";
 
-fn format_duration(duration: &Duration) -> String {
-    let mut formatted = String::new();
-    let mut sec = duration.as_secs();
-    let mut min = sec / 60;
-    let hrs = min / 60;
-    min %= 60;
-    sec %= 60;
-    if hrs > 0 {
-        formatted.push_str(&format!("{hrs}:{min:02}:"));
-    } else {
-        formatted.push_str(&format!("{min}:"));
+/// Remove the session parameters from the address bar and browser history.
+fn clear_query_string() {
+    let location = window().location();
+    let path = location.pathname().unwrap_or_default() + &location.hash().unwrap_or_default();
+    if let Err(e) = window()
+        .history()
+        .and_then(|h| h.replace_state_with_url(&JsValue::NULL, "", Some(&path)))
+    {
+        logging::error!("Cannot clear query string: {:?}", e);
+    }
+}
+
+/// Color theme of the app.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+    Contrast,
+}
+
+impl Theme {
+    const ALL: [Theme; 4] = [Theme::System, Theme::Light, Theme::Dark, Theme::Contrast];
+
+    /// Get the value of the `data-theme` attribute, if the theme overrides the system one.
+    fn attribute(self) -> Option<&'static str> {
+        match self {
+            Theme::System => None,
+            Theme::Light => Some("light"),
+            Theme::Dark => Some("dark"),
+            Theme::Contrast => Some("contrast"),
+        }
+    }
+
+    fn label(self, t: &Messages) -> &'static str {
+        match self {
+            Theme::System => t.theme_system,
+            Theme::Light => t.theme_light,
+            Theme::Dark => t.theme_dark,
+            Theme::Contrast => t.theme_contrast,
+        }
+    }
+}
+
+/// Apply the theme stored in `localStorage` to the document.
+fn use_theme() {
+    let (theme, _, _) = use_local_storage::<Theme, JsonSerdeCodec>(THEME_KEY);
+    Effect::new(move |_| {
+        let Some(root) = document().document_element() else {
+            return;
+        };
+        let result = match theme.get().attribute() {
+            Some(value) => root.set_attribute("data-theme", value),
+            None => root.remove_attribute("data-theme"),
+        };
+        if let Err(e) = result {
+            logging::error!("Cannot apply theme: {:?}", e);
+        }
+    });
+}
+
+/// Tracks removed by the user, that can still be put back into the queue.
+#[derive(Clone)]
+struct Undo<T> {
+    id: usize,
+    message: String,
+    tracks: Vec<(usize, T)>,
+}
+
+/// Error message shown to the user until dismissed.
+#[derive(Clone)]
+struct Toast {
+    id: usize,
+    message: String,
+}
+
+/// List of error messages shown to the user.
+#[derive(Clone, Copy)]
+struct Toasts {
+    list: RwSignal<Vec<Toast>>,
+    count: StoredValue<usize>,
+    t: Signal<&'static Messages>,
+}
+
+impl Toasts {
+    fn new(t: Signal<&'static Messages>) -> Self {
+        Self {
+            list: RwSignal::new(vec![]),
+            count: StoredValue::new(0),
+            t,
+        }
+    }
+
+    fn push(self, message: String) {
+        let id = self.count.get_value() + 1;
+        self.count.set_value(id);
+        self.list.update(|list| {
+            list.push(Toast { id, message });
+            if list.len() > MAX_TOASTS {
+                list.remove(0);
+            }
+        });
+    }
+
+    fn dismiss(self, id: usize) {
+        self.list.update(|list| list.retain(|toast| toast.id != id));
+    }
+
+    /// Log the error of a failed command and show its reason to the user.
+    ///
+    /// Returns `true` if the command succeeded.
+    fn report(self, result: Result<(), impl CommandError>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                logging::error!("Command failed: {}", e);
+                let t = self.t.get_untracked();
+                self.push(command_error_label(t, e.kind()).to_string());
+                false
+            }
+        }
+    }
+}
+
+fn command_error_label(t: &Messages, kind: CommandErrorKind) -> &'static str {
+    match kind {
+        CommandErrorKind::NotConnected => t.not_connected,
+        CommandErrorKind::Unsupported => t.command_unsupported,
+        CommandErrorKind::Forbidden => t.command_forbidden,
+        CommandErrorKind::Internal => t.command_failed,
     }
-    formatted.push_str(&format!("{sec:02}"));
-    formatted
+}
+
+/// Save the text as a file, through a temporary download link.
+fn download_file(name: &str, content: &str) -> Result<(), JsValue> {
+    let blob = Blob::new_with_str_sequence(&Array::of1(&content.into()))?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let link: HtmlAnchorElement = document().create_element("a")?.unchecked_into();
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    set_timeout(
+        move || {
+            if let Err(e) = Url::revoke_object_url(&url) {
+                logging::warn!("Cannot revoke download link: {:?}", e);
+            }
+        },
+        Duration::ZERO,
+    );
+    Ok(())
+}
+
+/// Read a playlist file chosen or dropped by the user.
+fn read_playlist(file: &File, import: RwSignal<Option<ImportedPlaylist>>) {
+    let loaded = Closure::once_into_js(move |text: JsValue| {
+        import.set(Some(playlist::import(
+            &text.as_string().unwrap_or_default(),
+        )));
+    });
+    let failed = Closure::once_into_js(|e: JsValue| {
+        logging::error!("Cannot read playlist file: {:?}", e);
+    });
+    if let Err(e) = call_method(&file.text(), "then", &[loaded, failed]) {
+        logging::error!("Cannot read playlist file: {:?}", e);
+    }
+}
+
+/// Ask the user to confirm a destructive action, unless it can be undone.
+fn confirm_destructive(undoable: bool, message: &str) -> bool {
+    undoable || window().confirm_with_message(message).unwrap_or(false)
+}
+
+/// Track whether the browser has a network connection.
+fn use_online() -> Signal<bool> {
+    let online = RwSignal::new(window().navigator().on_line());
+    let _ = use_event_listener(use_window(), ev::online, move |_| online.set(true));
+    let _ = use_event_listener(use_window(), ev::offline, move |_| online.set(false));
+    online.into()
+}
+
+/// Call a method of a JavaScript object that has no `web-sys` bindings.
+fn call_method(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+    let method: Function = Reflect::get(target, &name.into())?.dyn_into()?;
+    Reflect::apply(&method, target, &args.iter().collect::<Array>())
+}
+
+fn release_wake_lock(lock: &JsValue) {
+    if let Err(e) = call_method(lock, "release", &[]) {
+        logging::warn!("Cannot release wake lock: {:?}", e);
+    }
+}
+
+/// Keep the screen on while the calling component is mounted.
+///
+/// The browser releases the lock when the page is hidden, so it is requested again
+/// every time the page becomes visible.
+fn use_wake_lock() {
+    let sentinel = StoredValue::new_local(None::<JsValue>);
+    let visibility = use_document_visibility();
+    Effect::new(move |_| {
+        if visibility.get() != VisibilityState::Visible {
+            return;
+        }
+        let wake_lock = Reflect::get(&window().navigator(), &"wakeLock".into()).unwrap_or_default();
+        if wake_lock.is_undefined() {
+            logging::warn!("Screen Wake Lock API is not supported");
+            return;
+        }
+        let acquired = Closure::once_into_js(move |lock: JsValue| {
+            if let Some(Some(lock)) = sentinel.try_set_value(Some(lock)) {
+                release_wake_lock(&lock);
+            }
+        });
+        let failed = Closure::once_into_js(|e: JsValue| {
+            logging::warn!("Cannot keep the screen on: {:?}", e);
+        });
+        let result = call_method(&wake_lock, "request", &["screen".into()])
+            .and_then(|promise| call_method(&promise, "then", &[acquired, failed]));
+        if let Err(e) = result {
+            logging::warn!("Cannot keep the screen on: {:?}", e);
+        }
+    });
+    on_cleanup(move || {
+        if let Some(Some(lock)) = sentinel.try_update_value(Option::take) {
+            release_wake_lock(&lock);
+        }
+    });
+}
+
+/// Estimate when the current track started playing, in milliseconds since the epoch.
+fn use_track_start(position: Signal<Duration>, playing: Signal<bool>) -> Memo<Option<f64>> {
+    Memo::new(move |_| {
+        let position = position.get().as_secs_f64() * 1000.0;
+        playing.get().then(|| Date::now() - position)
+    })
+}
+
+/// Estimate when the queued track at the given index starts playing, in milliseconds since the epoch.
+///
+/// The loop setting does not change the estimate: looping appends finished tracks after the end
+/// of the queue, so every queued track still starts after the ones before it.
+fn estimated_start<T: TrackSnapshot>(started_at: f64, current: &T, queue: &[T], idx: usize) -> f64 {
+    let before = queue.iter().take(idx).map(TrackSnapshot::duration);
+    started_at + (current.duration() + before.sum::<Duration>()).as_secs_f64() * 1000.0
+}
+
+/// Get the name of the site that the extractor resolves tracks from.
+fn extractor_name(extractor: &str) -> &str {
+    let site = extractor.split(':').next().unwrap_or(extractor);
+    match site.to_lowercase().as_str() {
+        "youtube" => "YouTube",
+        "soundcloud" => "SoundCloud",
+        "bandcamp" => "Bandcamp",
+        "vimeo" => "Vimeo",
+        "twitch" => "Twitch",
+        "mixcloud" => "Mixcloud",
+        _ => site,
+    }
+}
+
+/// Estimate the playback position between player updates, which only report it on change.
+fn use_playback_position(position: Signal<Duration>, playing: Signal<bool>) -> Signal<Duration> {
+    let now = use_timestamp_with_options(UseTimestampOptions::default().interval(1000));
+    let since = Memo::new(move |_| (position.get(), playing.get(), now.get_untracked()));
+    Signal::derive(move || {
+        let (position, playing, since) = since.get();
+        if playing {
+            position + Duration::from_secs_f64((now.get() - since).max(0.0) / 1000.0)
+        } else {
+            position
+        }
+    })
 }
 
 #[component]
 fn DeleteIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <path d="M 14.75 13 C 14.75 11.62 16.65 10.5 19 10.5 C 21.35 10.5 23.25 11.62 23.25 13" fill="none" stroke="#000000" stroke-width="1" stroke-miterlimit="10"/>
-            <path d="M 12.5 28 L 14 14.75 L 24 14.75 L 25.5 28 Z" fill="#000000" transform="rotate(-180,19,21.38)"/>
-            <rect x="12" y="12.5" width="14" height="1" rx="0.06" ry="0.06" fill="#000000" stroke="#000000"/>
+            <path d="M 14.75 13 C 14.75 11.62 16.65 10.5 19 10.5 C 21.35 10.5 23.25 11.62 23.25 13" fill="none" stroke="currentColor" stroke-width="1" stroke-miterlimit="10"/>
+            <path d="M 12.5 28 L 14 14.75 L 24 14.75 L 25.5 28 Z" fill="currentColor" transform="rotate(-180,19,21.38)"/>
+            <rect x="12" y="12.5" width="14" height="1" rx="0.06" ry="0.06" fill="currentColor" stroke="currentColor"/>
         </svg>
     }
 }
@@ -59,8 +378,8 @@ fn DeleteIcon(frame: &'static str) -> impl IntoView {
 fn PreviousIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <rect x="13" y="9" width="4" height="20" fill="#000000"/>
-            <path d="M 25 9 L 13 19 L 25 29 Z" fill="#000000"/>
+            <rect x="13" y="9" width="4" height="20" fill="currentColor"/>
+            <path d="M 25 9 L 13 19 L 25 29 Z" fill="currentColor"/>
         </svg>
     }
 }
@@ -69,8 +388,8 @@ fn PreviousIcon(frame: &'static str) -> impl IntoView {
 fn NextIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <rect x="21" y="9" width="4" height="20" fill="#000000"/>
-            <path d="M 13 9 L 25 19 L 13 29 Z" fill="#000000"/>
+            <rect x="21" y="9" width="4" height="20" fill="currentColor"/>
+            <path d="M 13 9 L 25 19 L 13 29 Z" fill="currentColor"/>
         </svg>
     }
 }
@@ -79,7 +398,7 @@ fn NextIcon(frame: &'static str) -> impl IntoView {
 fn PlayIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <path d="M 13 9 L 28 19 L 13 29 Z" fill="#000000"/>
+            <path d="M 13 9 L 28 19 L 13 29 Z" fill="currentColor"/>
         </svg>
     }
 }
@@ -88,8 +407,8 @@ fn PlayIcon(frame: &'static str) -> impl IntoView {
 fn PauseIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <rect x="13" y="9" width="4" height="20" fill="#000000"/>
-            <rect x="21" y="9" width="4" height="20" fill="#000000"/>
+            <rect x="13" y="9" width="4" height="20" fill="currentColor"/>
+            <rect x="21" y="9" width="4" height="20" fill="currentColor"/>
         </svg>
     }
 }
@@ -98,8 +417,8 @@ fn PauseIcon(frame: &'static str) -> impl IntoView {
 fn LoopIcon(frame: &'static str) -> impl IntoView {
     view! {
         <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <path d="M 20.15 26.5 L 17.15 28 L 17.15 25 Z" fill="#000000" stroke="#000000" stroke-width="3" stroke-miterlimit="10"/>
-            <path d="M 26.5 26.5 L 26.5 11.5 L 11.5 11.5 L 11.5 26.5 L 17.15 26.5" fill="none" stroke="#000000" stroke-width="3"/>
+            <path d="M 20.15 26.5 L 17.15 28 L 17.15 25 Z" fill="currentColor" stroke="currentColor" stroke-width="3" stroke-miterlimit="10"/>
+            <path d="M 26.5 26.5 L 26.5 11.5 L 11.5 11.5 L 11.5 26.5 L 17.15 26.5" fill="none" stroke="currentColor" stroke-width="3"/>
         </svg>
     }
 }
@@ -107,9 +426,33 @@ fn LoopIcon(frame: &'static str) -> impl IntoView {
 #[component]
 fn InfoIcon(frame: &'static str) -> impl IntoView {
     view! {
-        <svg class="svg-icon" aria-hidden="true" viewBox={ frame }>
-            <rect x="17" y="9" width="4" height="4" fill="#bfb7a8"/>
-            <rect x="17" y="15" width="4" height="14" fill="#bfb7a8"/>
+        <svg class="svg-icon svg-icon-muted" aria-hidden="true" viewBox={ frame }>
+            <rect x="17" y="9" width="4" height="4" fill="currentColor"/>
+            <rect x="17" y="15" width="4" height="14" fill="currentColor"/>
+        </svg>
+    }
+}
+
+#[component]
+fn SettingsIcon(frame: &'static str) -> impl IntoView {
+    view! {
+        <svg class="svg-icon svg-icon-muted" aria-hidden="true" viewBox={ frame }>
+            <rect x="10" y="12" width="18" height="2" fill="currentColor"/>
+            <rect x="10" y="18" width="18" height="2" fill="currentColor"/>
+            <rect x="10" y="24" width="18" height="2" fill="currentColor"/>
+            <rect x="20" y="10" width="3" height="6" fill="currentColor"/>
+            <rect x="13" y="16" width="3" height="6" fill="currentColor"/>
+            <rect x="22" y="22" width="3" height="6" fill="currentColor"/>
+        </svg>
+    }
+}
+
+#[component]
+fn ExpandIcon(frame: &'static str) -> impl IntoView {
+    view! {
+        <svg class="svg-icon svg-icon-muted" aria-hidden="true" viewBox={ frame }>
+            <path d="M 10 16 L 10 10 L 16 10 M 22 10 L 28 10 L 28 16 M 28 22 L 28 28 L 22 28 M 16 28 L 10 28 L 10 22"
+                fill="none" stroke="currentColor" stroke-width="2"/>
         </svg>
     }
 }
@@ -118,163 +461,1742 @@ fn InfoIcon(frame: &'static str) -> impl IntoView {
 fn VolumeIcon(value: Signal<u8>) -> impl IntoView {
     view! {
         <svg width="1.3em" height="1.3em" aria-hidden="true" viewBox="0 0 22 20">
-            <path d="M -3 16 L 2 4 L 9 4 L 14 16 Z" fill="#000000" stroke="none" transform="rotate(-90,5.5,10)"/>
+            <path d="M -3 16 L 2 4 L 9 4 L 14 16 Z" fill="currentColor" stroke="none" transform="rotate(-90,5.5,10)"/>
             <path d="M 13 9.2 L 16.2 9.2 L 16.2 6 L 17.8 6 L 17.8 9.2 L 21 9.2 L 21 10.8 L 17.8 10.8 L 17.8 14 L 16.2 14 L 16.2 10.8 L 13 10.8 Z" transform="rotate(-45,17,10)"
-                fill=move || { if value.get() == 0 { "#000000" } else { "none" }}/>
+                fill=move || { if value.get() == 0 { "currentColor" } else { "none" }}/>
             <path d="M 6.68 15.26 C 5 14.04 4 12.08 4 10 C 4 7.92 5 5.96 6.68 4.74" fill="none" stroke-width="2" stroke-miterlimit="10" transform="rotate(-180,10.5,10)"
-                stroke=move || { if value.get() > 0 { "#000000" } else { "none" }}/>
+                stroke=move || { if value.get() > 0 { "currentColor" } else { "none" }}/>
             <path d="M 5.71 17.69 C 3.38 15.9 2 13.04 2 10 C 2 6.96 3.38 4.1 5.71 2.31" fill="none" stroke-width="2" stroke-miterlimit="10" transform="rotate(-180,11,10)"
-                stroke=move || { if value.get() > 50 { "#000000" } else { "none" }}/>
+                stroke=move || { if value.get() > 50 { "currentColor" } else { "none" }}/>
         </svg>
     }
 }
 
 #[component]
-fn TrackCard(track: Signal<impl TrackSnapshot + 'static>) -> impl IntoView {
+fn LoopButton(
+    enabled: Signal<bool>,
+    disabled: Signal<bool>,
+    on_toggle: impl Fn(bool) + 'static,
+) -> impl IntoView {
+    let t = use_messages();
+    view! {
+        <button class="btn-round" aria-pressed=move || enabled.get().to_string() disabled=disabled
+            on:click=move |_| on_toggle(!enabled.get_untracked())>
+            <LoopIcon frame=ICON_FRAME_LARGE/>
+            <span class="screenreader-only">{ move || t.get().loop_queue }</span>
+        </button>
+    }
+}
+
+#[component]
+fn VolumeSlider(
+    volume: Signal<u8>,
+    disabled: Signal<bool>,
+    on_change: impl Fn(String) + 'static,
+) -> impl IntoView {
+    let t = use_messages();
+    view! {
+        <label class="volume-widget">
+            <VolumeIcon value=volume/>
+            <span class="screenreader-only">{ move || t.get().volume }</span>
+            <input type="range" id="volume" min="0" max="100" step="1"
+                prop:value=volume
+                aria-valuetext=move || (t.get().volume_percent)(volume.get())
+                disabled=disabled
+                on:change=move |e| on_change(event_target_value(&e))/>
+        </label>
+    }
+}
+
+/// Find the byte ranges of case-insensitive matches of the query in the text.
+fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let mut ranges = vec![];
+    if query.is_empty() {
+        return ranges;
+    }
+    for (start, _) in text.char_indices() {
+        if ranges.last().is_some_and(|r: &Range<usize>| start < r.end) {
+            continue;
+        }
+        let mut end = start;
+        let mut lowered = text[start..].char_indices().flat_map(|(i, c)| {
+            let next = start + i + c.len_utf8();
+            c.to_lowercase().map(move |l| (next, l))
+        });
+        let found = query.iter().all(|q| {
+            lowered.next().is_some_and(|(next, l)| {
+                end = next;
+                l == *q
+            })
+        });
+        if found {
+            ranges.push(start..end);
+        }
+    }
+    ranges
+}
+
+/// Check if the track title, uploader or extractor matches the query.
+fn track_matches(track: &impl TrackSnapshot, query: &str) -> bool {
+    [track.title(), track.uploader(), track.extractor()]
+        .iter()
+        .any(|field| !match_ranges(field, query).is_empty())
+}
+
+#[component]
+fn Highlighted(text: Signal<String>, query: Signal<String>) -> impl IntoView {
+    move || {
+        let text = text.get();
+        let mut parts = vec![];
+        let mut last = 0;
+        for range in match_ranges(&text, &query.get()) {
+            parts.push(text[last..range.start].to_string().into_any());
+            parts.push(view! { <mark>{ text[range.clone()].to_string() }</mark> }.into_any());
+            last = range.end;
+        }
+        parts.push(text[last..].to_string().into_any());
+        parts
+    }
+}
+
+#[component]
+fn RequesterChip(name: Signal<String>, avatar: Signal<Option<String>>) -> impl IntoView {
+    let t = use_messages();
+    let label = move || (t.get().requested_by)(&name.get());
+    view! {
+        <span class="requester-chip" title=label>
+            <Show when=move || avatar.with(Option::is_some)>
+                <img src=move || avatar.get() loading="lazy" alt="" class="requester-avatar"/>
+            </Show>
+            <span class="screenreader-only">{ label }</span>
+            <span aria-hidden="true">{ name }</span>
+        </span>
+    }
+}
+
+#[component]
+fn TrackCard(
+    track: Signal<impl TrackSnapshot + 'static>,
+    #[prop(optional)] highlight: Signal<String>,
+) -> impl IntoView {
+    let t = use_messages();
     view! {
         <div class="track-card">
-           <img src=move || track.get().thumbnail().map(|s| s.to_string()) class="track-thumbnail"/>
+           <img src=move || track.get().thumbnail().map(|s| s.to_string()) loading="lazy" class="track-thumbnail"
+                alt=move || (t.get().thumbnail_of)(track.get().title())/>
             <div class="track-card-labels">
-                <a href=move || track.get().webpage_url().to_string() target="_blank" class="track-title">{move || track.get().title().to_string()}</a>
-                <a href=move || track.get().uploader_url().map(|s| s.to_string()) target="_blank">{move || track.get().uploader().to_string()}</a>
+                <a href=move || track.get().webpage_url().to_string() target="_blank" rel="noreferrer noopener" class="track-title">
+                    <Highlighted text=Signal::derive(move || track.get().title().to_string()) query=highlight/>
+                </a>
+                <span class="track-card-meta">
+                    <a href=move || track.get().uploader_url().map(|s| s.to_string()) target="_blank" rel="noreferrer noopener">
+                        <Highlighted text=Signal::derive(move || track.get().uploader().to_string()) query=highlight/>
+                    </a>
+                    <Show when=move || track.with(|t| t.requester().is_some())>
+                        <RequesterChip
+                            name=Signal::derive(move || track.with(|t| t.requester().map(|r| r.display_name.to_string()).unwrap_or_default()))
+                            avatar=Signal::derive(move || track.with(|t| t.requester().and_then(|r| r.avatar_url).map(|s| s.to_string())))/>
+                    </Show>
+                </span>
             </div>
         </div>
     }
 }
 
+/// Key the rows by track ID, telling repeated tracks apart by their order.
+fn row_keys<T: TrackSnapshot>(rows: Vec<(usize, T)>) -> Vec<((String, usize), usize, T)> {
+    let mut seen = HashMap::<String, usize>::new();
+    let rows = rows.into_iter();
+    rows.map(|(idx, entry)| {
+        let count = seen.entry(entry.id().to_string()).or_default();
+        *count += 1;
+        ((entry.id().to_string(), *count), idx, entry)
+    })
+    .collect()
+}
+
+/// List that only mounts the rows visible in its scroll container.
+#[component]
+fn VirtualList<T, S, R, V>(
+    container: NodeRef<Main>,
+    len: Signal<usize>,
+    slice: S,
+    row: R,
+) -> impl IntoView
+where
+    T: TrackSnapshot + 'static,
+    S: Fn(Range<usize>) -> Vec<(usize, T)> + Send + Sync + 'static,
+    R: Fn(Signal<usize>, T) -> V + Clone + Send + Sync + 'static,
+    V: IntoView + 'static,
+{
+    let UseScrollReturn { y, .. } = use_scroll(container);
+    let UseElementSizeReturn { height, .. } = use_element_size(container);
+    let window = Memo::new(move |_| {
+        let len = len.get();
+        let first = (y.get() / ROW_HEIGHT) as usize;
+        let first = first.saturating_sub(OVERSCAN_ROWS).min(len);
+        let count = (height.get() / ROW_HEIGHT).ceil() as usize + 2 * OVERSCAN_ROWS;
+        first..len.min(first + count)
+    });
+    let slice = Arc::new(slice);
+    // Rows are keyed by track, so that they survive changes to the queue above them.
+    let rows = {
+        let slice = slice.clone();
+        move || row_keys(slice(window.get()))
+    };
+    let indices = Memo::new({
+        let rows = rows.clone();
+        move |_| {
+            rows()
+                .into_iter()
+                .map(|(key, idx, _)| (key, idx))
+                .collect::<Vec<_>>()
+        }
+    });
+    view! {
+        <ol style:padding-top=move || format!("{}px", window.get().start as f64 * ROW_HEIGHT)
+            style:padding-bottom=move || format!("{}px", (len.get() - window.get().end) as f64 * ROW_HEIGHT)>
+            <For each=rows
+                 key=|(key, _, _)| key.clone()
+                 children=move |(key, idx, entry)| {
+                     let idx = Signal::derive(move || indices.with(|rows| {
+                         rows.iter().find(|(k, _)| *k == key).map_or(idx, |(_, idx)| *idx)
+                     }));
+                     row(idx, entry)
+                 }/>
+        </ol>
+    }
+}
+
 #[component]
 pub fn Player() -> impl IntoView {
     let query_params = use_query_map().get_untracked();
-    let access_code = query_params.get("ac").unwrap().parse::<i64>().unwrap();
-    let token = query_params.get("rt").unwrap().to_string();
-    let rcs_bytes = BASE64_URL_SAFE_NO_PAD
-        .decode(query_params.get("ws").unwrap())
-        .unwrap();
-    let remote_server = String::from_utf8(rcs_bytes).unwrap();
-    let remote_url = format!("{remote_server}/{access_code}");
-
-    let client = RemotePlayer::new(&remote_url, &token, access_code);
-    let snapshot = Signal::derive(move || {
-        let state = client.state.get();
-        match serde_json::from_str(state.as_deref().unwrap_or("")) {
-            Ok(s) => s,
-            Err(e) => {
-                logging::error!("Invalid snapshot: {}", e);
-                PlayerModel::default()
-            }
+    let (stored, set_stored, _) =
+        use_session_storage::<Option<SessionParams>, JsonSerdeCodec>(SESSION_STORAGE_KEY);
+
+    // Sessions opened from a link stay in session storage until the user saves them.
+    let (saved, set_saved, _) =
+        use_local_storage::<Vec<SavedSession>, JsonSerdeCodec>(SAVED_SESSIONS_KEY);
+
+    use_service_worker_with_options(
+        UseServiceWorkerOptions::default().script_url(SERVICE_WORKER_URL),
+    );
+    let online = use_online();
+    use_theme();
+    provide_locale();
+    let t = use_messages();
+    let toasts = Toasts::new(t);
+    match SessionParams::from_query(&query_params) {
+        Ok(Some(params)) => {
+            set_stored.set(Some(params));
+            clear_query_string();
         }
-    });
+        Ok(None) => {}
+        Err(e) => {
+            logging::error!("Invalid session link: {}", e);
+            toasts.push(t.get_untracked().invalid_link.to_string());
+        }
+    }
+
+    // Without a connection at startup there is nothing to show, later the last state stays visible.
+    let started =
+        Memo::new(move |started: Option<&bool>| started.copied().unwrap_or(false) || online.get());
+    view! {
+        <Show when=move || started.get() && !online.get()>
+            <div class="offline-banner" role="status">
+                <strong>{ move || t.get().offline }</strong>
+                <span>{ move || t.get().offline_info }</span>
+            </div>
+        </Show>
+        { move || match stored.get() {
+            _ if !started.get() => view! { <OfflineScreen/> }.into_any(),
+            Some(params) => view! { <PlayerView params saved set_saved set_session=set_stored toasts/> }.into_any(),
+            None => view! { <SessionPicker saved set_saved set_session=set_stored toasts/> }.into_any(),
+        } }
+    }
+}
+
+/// Show the error toasts, followed by any other toasts given as children.
+#[component]
+fn ToastList(toasts: Toasts, #[prop(optional)] children: Option<Children>) -> impl IntoView {
+    let t = use_messages();
+    view! {
+        <div class="toasts">
+            <For each=move || toasts.list.get() key=|toast| toast.id let:toast>
+                <div class="toast toast-error" role="alert">
+                    <span>{ toast.message }</span>
+                    <button class="btn-text" on:click=move |_| toasts.dismiss(toast.id)>
+                        { move || t.get().dismiss }
+                    </button>
+                </div>
+            </For>
+            { children.map(|children| children()) }
+        </div>
+    }
+}
 
-    let client2 = client.clone();
+#[component]
+fn OfflineScreen() -> impl IntoView {
+    let t = use_messages();
     view! {
-        <div class="container">
+        <div class="offline-screen">
             <header class="header">
-                <span>Next up</span>
-                <button class="btn-inline" popovertarget="copyright-dialog">
-                    <InfoIcon frame=ICON_FRAME_SMALL/>
-                    <span class="screenreader-only">Show copyright info</span>
-                </button>
+                <span>{ move || t.get().offline }</span>
             </header>
-            <main class="track-list">
-                <ol>
-                    <For each=move || snapshot.get().queue().to_vec()
-                         key=move |entry| entry.id().to_string()
-                         let: entry>
-                        <li>
-                            <div class="track">
-                                <TrackCard track=Signal::stored(entry.clone())/>
-                                <div class="track-controls">
-                                    <span class="track-duration">{ format_duration(&entry.duration()) }</span>
-                                    <button class="btn-inline" on:click={
-                                            let entry = entry.clone();
-                                            let client = client2.clone();
-                                            move |_| {
-                                            let idx = snapshot.get().queue().iter().position(|e| e.id() == entry.id()).unwrap();
-                                            client.move_to(idx, entry.id()).unwrap(); }}>
-                                        <PlayIcon frame=ICON_FRAME_SMALL/>
-                                        <span class="screenreader-only">Play</span>
+            <p>{ move || t.get().offline_info }</p>
+        </div>
+    }
+}
+
+#[component]
+fn SessionPicker(
+    saved: Signal<Vec<SavedSession>>,
+    set_saved: WriteSignal<Vec<SavedSession>>,
+    set_session: WriteSignal<Option<SessionParams>>,
+    toasts: Toasts,
+) -> impl IntoView {
+    let (name, set_name) = signal(String::new());
+    let (link, set_link) = signal(String::new());
+    let (link_error, set_link_error) = signal(false);
+    let t = use_messages();
+    view! {
+        <div class="session-picker">
+            <header class="header">
+                <span>{ move || t.get().sessions }</span>
+            </header>
+            <Show
+                when=move || saved.with(|s| !s.is_empty())
+                fallback=move || view! { <p>{ move || t.get().no_saved_sessions }</p> }
+            >
+                <ul class="session-list">
+                    <For
+                        each=move || saved.get().into_iter().enumerate()
+                        key=|(idx, session)| (*idx, session.name.clone())
+                        children=move |(idx, session)| {
+                            let params = session.params.clone();
+                            view! {
+                                <li class="session-item">
+                                    <button class="btn-text session-open"
+                                        on:click=move |_| set_session.set(Some(params.clone()))>
+                                        <span class="session-name">{ session.name }</span>
+                                        <span class="session-host">
+                                            { session.params.default_name() }
+                                        </span>
                                     </button>
-                                    <button class="btn-inline" on:click={
-                                            let entry = entry.clone();
-                                            let client = client2.clone();
-                                            move |_| {
-                                            let idx = snapshot.get().queue().iter().position(|e| e.id() == entry.id()).unwrap();
-                                            client.remove(idx, entry.id()).unwrap(); }}>
-                                        <DeleteIcon frame=ICON_FRAME_SMALL/>
-                                        <span class="screenreader-only">Remove</span>
+                                    <button class="btn-text"
+                                        on:click=move |_| set_saved.update(|s| { s.remove(idx); })>
+                                        { move || t.get().forget_session }
                                     </button>
-                                </div>
-                            </div>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+            </Show>
+            <form class="session-form" on:submit=move |e| {
+                e.prevent_default();
+                match SessionParams::from_link(&link.get()) {
+                    Ok(params) => {
+                        let name = Some(name.get().trim().to_string()).filter(|n| !n.is_empty());
+                        set_saved.update(|s| save_session(s, params.clone(), name));
+                        set_session.set(Some(params));
+                    }
+                    Err(e) => {
+                        logging::warn!("Invalid session link: {}", e);
+                        set_link_error.set(true);
+                    }
+                }
+            }>
+                <label>
+                    <span>{ move || t.get().session_name }</span>
+                    <input type="text" placeholder=move || t.get().optional
+                        prop:value=name
+                        on:input=move |e| set_name.set(event_target_value(&e))/>
+                </label>
+                <label>
+                    <span>{ move || t.get().discord_link }</span>
+                    <input type="url" required
+                        prop:value=link
+                        on:input=move |e| set_link.set(event_target_value(&e))/>
+                </label>
+                <p class="error">{ move || link_error.get().then(|| t.get().invalid_link) }</p>
+                <button class="btn-text" type="submit">{ move || t.get().add_session }</button>
+            </form>
+            <ToastList toasts/>
+        </div>
+    }
+}
+
+#[component]
+fn SessionSwitcher(
+    current: SessionParams,
+    saved: Signal<Vec<SavedSession>>,
+    set_saved: WriteSignal<Vec<SavedSession>>,
+    set_session: WriteSignal<Option<SessionParams>>,
+) -> impl IntoView {
+    let t = use_messages();
+    let current_name = current.default_name();
+    let unsaved = Memo::new({
+        let current = current.clone();
+        move |_| saved.with(|s| !s.iter().any(|s| s.params.same_session(&current)))
+    });
+    let save = {
+        let current = current.clone();
+        move |_| set_saved.update(|s| save_session(s, current.clone(), None))
+    };
+    view! {
+        <label class="session-switcher">
+            <span class="screenreader-only">{ move || t.get().switch_session }</span>
+            <select on:change=move |e| {
+                let params = event_target_value(&e)
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|idx| saved.with_untracked(|s| s.get(idx).cloned()))
+                    .map(|s| s.params);
+                set_session.set(params);
+            }>
+                <Show when=move || unsaved.get()>
+                    <option value="" selected>{ current_name.clone() }</option>
+                </Show>
+                { move || saved.get().into_iter().enumerate().map(|(idx, session)| {
+                    let selected = session.params.same_session(&current);
+                    view! { <option value=idx selected=selected>{ session.name }</option> }
+                }).collect_view() }
+                <option value="">{ move || t.get().manage_sessions }</option>
+            </select>
+        </label>
+        <Show when=move || unsaved.get()>
+            <button class="btn-text" on:click=save.clone()>{ move || t.get().save_session }</button>
+        </Show>
+    }
+}
+
+#[component]
+fn QrCode(data: Signal<String>) -> impl IntoView {
+    let t = use_messages();
+    let code = Memo::new(move |_| {
+        let code = data
+            .with(|data| qrcode::QrCode::new(data.as_bytes()))
+            .ok()?;
+        let width = code.width();
+        let mut path = String::new();
+        for (i, color) in code.to_colors().into_iter().enumerate() {
+            if color == qrcode::Color::Dark {
+                let (x, y) = (i % width + QR_QUIET_ZONE, i / width + QR_QUIET_ZONE);
+                path.push_str(&format!("M{x} {y}h1v1h-1z"));
+            }
+        }
+        Some((width + 2 * QR_QUIET_ZONE, path))
+    });
+    move || match code.get() {
+        Some((size, path)) => view! {
+            <svg class="qr-code" role="img" aria-label=move || t.get().qr_code_label
+                viewBox=format!("0 0 {size} {size}") shape-rendering="crispEdges">
+                <rect width=size height=size fill="#ffffff"/>
+                <path d=path fill="#000000"/>
+            </svg>
+        }
+        .into_any(),
+        None => view! { <p>{ move || t.get().qr_code_too_long }</p> }.into_any(),
+    }
+}
+
+fn state_label(t: &Messages, state: MusicPlayerState) -> &'static str {
+    match state {
+        MusicPlayerState::Idle => t.player_idle,
+        MusicPlayerState::Playing => t.player_playing,
+        MusicPlayerState::Paused => t.player_paused,
+        MusicPlayerState::Stopped => t.player_stopped,
+        MusicPlayerState::Disconnected => t.player_disconnected,
+    }
+}
+
+/// Announce track and player state changes to screen readers.
+#[component]
+fn LiveAnnouncer<T: TrackSnapshot + 'static>(
+    current: Signal<Option<T>>,
+    state: Signal<MusicPlayerState>,
+) -> impl IntoView {
+    let t = use_messages();
+    let current_id = Memo::new(move |_| current.with(|c| c.as_ref().map(|c| c.id().to_string())));
+    let state = Memo::new(move |_| state.get());
+    let (message, set_message) = signal(String::new());
+    Effect::new(move |prev: Option<(Option<String>, MusicPlayerState)>| {
+        let next = (current_id.get(), state.get());
+        if let Some((prev_id, prev_state)) = prev {
+            let t = t.get_untracked();
+            let track = current.get_untracked().filter(|_| next.0 != prev_id);
+            if let Some(track) = track {
+                set_message.set((t.now_playing)(track.title(), track.uploader()));
+            } else if next.1 != prev_state {
+                set_message.set(state_label(t, next.1).to_string());
+            }
+        }
+        next
+    });
+    view! {
+        <div class="screenreader-only" role="status" aria-live="polite" aria-atomic="true">
+            { message }
+        </div>
+    }
+}
+
+/// Show a notification when a new track starts playing, unless the app is focused.
+#[component]
+fn TrackNotifier<T: TrackSnapshot + 'static>(current: Signal<Option<T>>) -> impl IntoView {
+    let UseWebNotificationReturn { show, .. } = use_web_notification_with_options(
+        UseWebNotificationOptions::default()
+            .tag("acme-bot-remote.track")
+            .renotify(true),
+    );
+    let focused = use_window_focus();
+    let current_id = Memo::new(move |_| current.with(|t| t.as_ref().map(|t| t.id().to_string())));
+    let current_id = signal_throttled(current_id, NOTIFICATION_INTERVAL_MS);
+    Effect::new(move |prev: Option<Option<String>>| {
+        let id: Option<String> = current_id.get();
+        let changed = prev.is_some_and(|prev| id.is_some() && id != prev);
+        if changed && !focused.get_untracked() {
+            let track = current
+                .get_untracked()
+                .filter(|t| Some(t.id()) == id.as_deref());
+            if let Some(track) = track {
+                let options = ShowOptions::default()
+                    .title(track.title())
+                    .body(track.uploader());
+                show(match track.thumbnail() {
+                    Some(thumbnail) => options.icon(thumbnail),
+                    None => options,
+                });
+            }
+        }
+        id
+    });
+}
+
+#[component]
+fn ThemePicker() -> impl IntoView {
+    let (theme, set_theme, _) = use_local_storage::<Theme, JsonSerdeCodec>(THEME_KEY);
+    let t = use_messages();
+    view! {
+        <label class="theme-picker">
+            <span class="screenreader-only">{ move || t.get().theme }</span>
+            <select on:change=move |e| {
+                let idx: usize = event_target_value(&e).parse().unwrap_or_default();
+                set_theme.set(Theme::ALL[idx]);
+            }>
+                { Theme::ALL.into_iter().enumerate().map(|(idx, th)| view! {
+                    <option value=idx selected=move || theme.get() == th>{ move || th.label(t.get()) }</option>
+                }).collect_view() }
+            </select>
+        </label>
+    }
+}
+
+#[component]
+fn LanguagePicker() -> impl IntoView {
+    let (chosen, set_chosen, _) = use_local_storage::<Option<Locale>, JsonSerdeCodec>(LOCALE_KEY);
+    let t = use_messages();
+    view! {
+        <label class="theme-picker">
+            <span class="screenreader-only">{ move || t.get().language }</span>
+            <select on:change=move |e| {
+                let idx = event_target_value(&e).parse::<usize>().ok();
+                set_chosen.set(idx.and_then(|i| Locale::ALL.get(i).copied()));
+            }>
+                <option value="" selected=move || chosen.with(Option::is_none)>
+                    { move || t.get().language_auto }
+                </option>
+                { Locale::ALL.into_iter().enumerate().map(|(idx, l)| view! {
+                    <option value=idx lang=l.tag() selected=move || chosen.get() == Some(l)>{ l.name() }</option>
+                }).collect_view() }
+            </select>
+        </label>
+    }
+}
+
+#[component]
+fn SettingsDialog(
+    client: RemotePlayer,
+    toasts: Toasts,
+    params: SessionParams,
+    notify: Signal<bool>,
+    set_notify: WriteSignal<bool>,
+) -> impl IntoView {
+    let location = window().location();
+    let base = format!(
+        "{}{}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default()
+    );
+    let pairing = client.pairing;
+    let link = Signal::derive(move || match pairing.get() {
+        Some(p) => SessionParams {
+            token: p.token,
+            ..params.clone()
+        }
+        .to_link(&base),
+        None => params.to_link(&base),
+    });
+    let supports_pairing = {
+        let client = client.clone();
+        move || client.supports_pairing()
+    };
+    let t = use_messages();
+    view! {
+        <dialog id="settings-dialog" class="settings-dialog" popover>
+            <h2>{ move || t.get().pair_device }</h2>
+            <p>{ move || t.get().pair_device_info }</p>
+            <QrCode data=link/>
+            <Show when=supports_pairing>
+                <Show
+                    when=move || pairing.with(Option::is_some)
+                    fallback={
+                        let client = client.clone();
+                        move || {
+                            let client = client.clone();
+                            view! {
+                                <button class="btn-text" on:click=move |_| {
+                                    toasts.report(client.request_pairing());
+                                }>{ move || t.get().use_short_lived_link }</button>
+                            }
+                        }
+                    }
+                >
+                    <p>{ move || pairing.get().map(|p| (t.get().link_expires_in)((p.ttl + 59) / 60)) }</p>
+                </Show>
+            </Show>
+            <h2>{ move || t.get().theme }</h2>
+            <ThemePicker/>
+            <h2>{ move || t.get().language }</h2>
+            <LanguagePicker/>
+            <h2>{ move || t.get().notifications }</h2>
+            <label class="settings-option">
+                <input type="checkbox"
+                    prop:checked=notify
+                    on:change=move |e| set_notify.set(event_target_checked(&e))/>
+                <span>{ move || t.get().notify_track_change }</span>
+            </label>
+            <button popovertarget="settings-dialog" autofocus>{ move || t.get().close }</button>
+        </dialog>
+    }
+}
+
+#[component]
+fn ExportDialog(client: RemotePlayer, toasts: Toasts) -> impl IntoView {
+    let snapshot = client.state;
+    let t = use_messages();
+    let export = move |format: PlaylistFormat| {
+        let tracks: Vec<_> = snapshot
+            .with_untracked(|s| s.current().into_iter().chain(s.queue()).cloned().collect());
+        let name = format!("acme-bot-queue.{}", format.extension());
+        let result = format
+            .export(&tracks)
+            .map_err(|e| e.to_string())
+            .and_then(|content| download_file(&name, &content).map_err(|e| format!("{e:?}")));
+        if let Err(e) = result {
+            logging::error!("Cannot export queue: {}", e);
+            toasts.push(t.get_untracked().export_failed.to_string());
+        }
+    };
+    view! {
+        <dialog id="export-dialog" class="settings-dialog" popover>
+            <h2>{ move || t.get().export_queue }</h2>
+            <p>{ move || t.get().export_info }</p>
+            <div class="export-formats">
+                { PlaylistFormat::ALL.into_iter().map(|format| view! {
+                    <button class="btn-text" popovertarget="export-dialog" popovertargetaction="hide"
+                        on:click=move |_| export(format)>
+                        { format.name() }
+                    </button>
+                }).collect_view() }
+            </div>
+            <button popovertarget="export-dialog" autofocus>{ move || t.get().close }</button>
+        </dialog>
+    }
+}
+
+fn import_error_label(t: &Messages, error: &ImportError) -> String {
+    match *error {
+        ImportError::InvalidUrl(line) => (t.invalid_url_line)(line),
+        ImportError::UnclosedTrack(line) => (t.unclosed_track_line)(line),
+        ImportError::MissingLocation(line) => (t.missing_location_line)(line),
+        ImportError::InvalidJson(line) => (t.invalid_json_line)(line),
+    }
+}
+
+/// Popover with a preview of the tracks read from a playlist file, before they are enqueued.
+#[component]
+fn ImportDialog(
+    client: RemotePlayer,
+    toasts: Toasts,
+    import: RwSignal<Option<ImportedPlaylist>>,
+) -> impl IntoView {
+    let t = use_messages();
+    let dialog = NodeRef::<Dialog>::new();
+    Effect::new(move |_| {
+        let Some(dialog) = dialog.get() else {
+            return;
+        };
+        if import.with(Option::is_some) && !dialog.matches(":popover-open").unwrap_or(false) {
+            if let Err(e) = dialog.show_popover() {
+                logging::error!("Cannot open dialog: {:?}", e);
+            }
+        }
+    });
+    let close = move || {
+        import.set(None);
+        if let Some(Err(e)) = dialog.get_untracked().map(|d| d.hide_popover()) {
+            logging::error!("Cannot close dialog: {:?}", e);
+        }
+    };
+    let entries = Signal::derive(move || {
+        import.with(|i| i.as_ref().map(|i| i.entries.clone()).unwrap_or_default())
+    });
+    let errors = Signal::derive(move || {
+        import.with(|i| i.as_ref().map(|i| i.errors.clone()).unwrap_or_default())
+    });
+    view! {
+        <dialog id="import-dialog" class="settings-dialog import-dialog" popover node_ref=dialog>
+            <h2>{ move || t.get().import_queue }</h2>
+            <Show when=move || import.with(Option::is_some)
+                  fallback=move || view! {
+                      <p>{ move || t.get().import_info }</p>
+                      <input type="file" accept=".m3u,.m3u8,.xspf,.json,.txt,audio/x-mpegurl,application/xspf+xml,application/json,text/plain" autofocus
+                          on:change=move |e| {
+                              let input: HtmlInputElement = event_target(&e);
+                              if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                  read_playlist(&file, import);
+                              }
+                              input.set_value("");
+                          }/>
+                      <div class="track-details-actions">
+                          <button popovertarget="import-dialog">{ move || t.get().close }</button>
+                      </div>
+                  }>
+                <p>{ move || match entries.with(Vec::len) {
+                    0 => t.get().import_empty.to_string(),
+                    n => (t.get().import_preview)(n),
+                } }</p>
+                <ol class="import-preview">
+                    <For each=move || entries.get().into_iter().enumerate() key=|(idx, _)| *idx let:((_, entry))>
+                        <li>
+                            <span class="track-title">{ entry.title.clone().unwrap_or_else(|| entry.url.clone()) }</span>
+                            <span class="import-url">{ entry.url }</span>
                         </li>
                     </For>
                 </ol>
+                <Show when=move || errors.with(|e| !e.is_empty())>
+                    <p>{ move || t.get().import_skipped }</p>
+                    <ul class="import-errors">
+                        <For each=move || errors.get().into_iter().enumerate() key=|(idx, _)| *idx let:((_, error))>
+                            <li>{ move || import_error_label(t.get(), &error) }</li>
+                        </For>
+                    </ul>
+                </Show>
+                <div class="track-details-actions">
+                    <button class="btn-text" autofocus disabled={
+                        let client = client.clone();
+                        move || entries.with(Vec::is_empty) || !client.available(PlayerOp::Enqueue)
+                    } on:click={
+                        let client = client.clone();
+                        move |_| {
+                            let urls: Vec<_> = entries.with_untracked(|e| e.iter().map(|e| e.url.clone()).collect());
+                            if toasts.report(client.enqueue(&urls)) {
+                                close();
+                            }
+                        }}>
+                        { move || t.get().add_to_queue }
+                    </button>
+                    <button on:click=move |_| close()>{ move || t.get().cancel }</button>
+                </div>
+            </Show>
+        </dialog>
+    }
+}
+
+/// Track shown in the details popover, either a queue entry or the current track.
+#[derive(Clone, Debug, PartialEq)]
+struct DetailsTarget {
+    index: Option<usize>,
+    id: String,
+}
+
+/// Popover with the full metadata of a track.
+#[component]
+fn TrackDetails(
+    client: RemotePlayer,
+    target: RwSignal<Option<DetailsTarget>>,
+    started_at: Memo<Option<f64>>,
+) -> impl IntoView {
+    let snapshot = client.state;
+    let t = use_messages();
+    let locale = use_locale();
+    let UseClipboardReturn {
+        is_supported,
+        copied,
+        copy,
+        ..
+    } = use_clipboard();
+    let track = Signal::derive(move || {
+        let target = target.get()?;
+        snapshot.with(|s| {
+            let track = match target.index {
+                Some(idx) => s.queue().get(idx),
+                None => s.current.as_ref(),
+            };
+            track.filter(|track| track.id() == target.id).cloned()
+        })
+    });
+    let field = move |f: fn(&QueueEntry) -> &str| {
+        move || track.with(|t| t.as_ref().map(|t| f(t).to_string()))
+    };
+    let start = Signal::derive(move || {
+        let started_at = started_at.get()?;
+        let index = target.with(|target| target.as_ref().map(|target| target.index))?;
+        let time = match index {
+            Some(idx) => snapshot.with(|s| {
+                Some(estimated_start(
+                    started_at,
+                    s.current.as_ref()?,
+                    s.queue(),
+                    idx,
+                ))
+            })?,
+            None => started_at,
+        };
+        Some((t.get().approximately)(&format_time(time, locale.get())))
+    });
+    view! {
+        <dialog id="track-details" class="track-details" popover>
+            <Show when=move || track.with(Option::is_some)>
+                <h2>{ field(QueueEntry::title) }</h2>
+                <span class="extractor-badge">{ move || track.with(|t| t.as_ref().map(|t| extractor_name(t.extractor()).to_string())) }</span>
+                <dl>
+                    <dt>{ move || t.get().details_uploader }</dt>
+                    <dd>
+                        <a href=move || track.with(|t| t.as_ref().and_then(|t| t.uploader_url()).map(|s| s.to_string()))
+                            target="_blank" rel="noreferrer noopener">{ field(QueueEntry::uploader) }</a>
+                    </dd>
+                    <Show when=move || track.with(|t| t.as_ref().is_some_and(|t| t.requester().is_some()))>
+                        <dt>{ move || t.get().details_requester }</dt>
+                        <dd>{ move || track.with(|t| t.as_ref().and_then(|t| t.requester()).map(|r| r.display_name.to_string())) }</dd>
+                    </Show>
+                    <dt>{ move || t.get().details_duration }</dt>
+                    <dd>{ field(QueueEntry::duration_string) }</dd>
+                    <dt>{ move || t.get().details_position }</dt>
+                    <dd>{ move || target.with(|target| match target.as_ref().and_then(|target| target.index) {
+                        Some(idx) => (idx + 1).to_string(),
+                        None => t.get().now_playing_title.to_string(),
+                    }) }</dd>
+                    <Show when=move || start.with(Option::is_some)>
+                        <dt>{ move || {
+                            let current = target.with(|target| target.as_ref().is_some_and(|target| target.index.is_none()));
+                            if current { t.get().started_at } else { t.get().starts_at }
+                        } }</dt>
+                        <dd>{ start }</dd>
+                    </Show>
+                    <dt>{ move || t.get().details_link }</dt>
+                    <dd>
+                        <a href=field(QueueEntry::webpage_url) target="_blank" rel="noreferrer noopener">{ field(QueueEntry::webpage_url) }</a>
+                    </dd>
+                    <dt>{ move || t.get().details_extractor }</dt>
+                    <dd>{ field(QueueEntry::extractor) }</dd>
+                    <dt>{ move || t.get().details_id }</dt>
+                    <dd><code>{ field(QueueEntry::id) }</code></dd>
+                </dl>
+            </Show>
+            <div class="track-details-actions">
+                <Show when=move || is_supported.get() && track.with(Option::is_some)>
+                    <button class="btn-text" on:click={
+                        let copy = copy.clone();
+                        move |_| {
+                            if let Some(url) = field(QueueEntry::webpage_url)() {
+                                copy(&url);
+                            }
+                        }}>
+                        { move || if copied.get() { t.get().link_copied } else { t.get().copy_link } }
+                    </button>
+                </Show>
+                <button popovertarget="track-details" autofocus>{ move || t.get().close }</button>
+            </div>
+        </dialog>
+    }
+}
+
+/// Full-screen view of the current track, for remotes left on a TV or tablet.
+#[component]
+fn NowPlaying(client: RemotePlayer, toasts: Toasts, open: RwSignal<bool>) -> impl IntoView {
+    let snapshot = client.state;
+    let t = use_messages();
+    let locale = use_locale();
+    let current = Signal::derive(move || snapshot.with(|s| s.current.clone()));
+    let playing = Signal::derive(move || snapshot.with(|s| s.state() == MusicPlayerState::Playing));
+    let position = use_playback_position(
+        Signal::derive(move || snapshot.with(|s| s.position())),
+        playing,
+    );
+    let duration = Signal::derive(move || {
+        current.with(|c| c.as_ref().map(|c| c.duration()).unwrap_or_default())
+    });
+    let elapsed = Signal::derive(move || position.get().min(duration.get()));
+    let up_next = move || {
+        snapshot.with(|s| {
+            s.queue()
+                .iter()
+                .take(UP_NEXT_COUNT)
+                .cloned()
+                .enumerate()
+                .collect::<Vec<_>>()
+        })
+    };
+    use_wake_lock();
+    let dialog = NodeRef::<Dialog>::new();
+    Effect::new(move |_| {
+        if let Some(dialog) = dialog.get() {
+            if let Err(e) = dialog
+                .show_modal()
+                .and_then(|_| dialog.request_fullscreen())
+            {
+                logging::error!("Cannot open dialog: {:?}", e);
+            }
+        }
+    });
+    on_cleanup(|| {
+        if document().fullscreen_element().is_some() {
+            document().exit_fullscreen();
+        }
+    });
+    view! {
+        <dialog class="now-playing" node_ref=dialog on:close=move |_| open.set(false)>
+            <header class="now-playing-header">
+                <h2>{ move || t.get().now_playing_title }</h2>
+                <button class="btn-text" on:click=move |_| open.set(false)>{ move || t.get().close }</button>
+            </header>
+            <Show when=move || current.with(Option::is_some)
+                  fallback=move || view! { <p class="now-playing-empty">{ move || t.get().nothing_playing }</p> }>
+                <div class="now-playing-track">
+                    <img class="now-playing-artwork"
+                        src=move || current.with(|c| c.as_ref().and_then(|c| c.thumbnail()).map(|s| s.to_string()))
+                        alt=move || current.with(|c| c.as_ref().map(|c| (t.get().thumbnail_of)(c.title())))/>
+                    <p class="now-playing-title">{ move || current.with(|c| c.as_ref().map(|c| c.title().to_string())) }</p>
+                    <p class="now-playing-uploader">{ move || current.with(|c| c.as_ref().map(|c| c.uploader().to_string())) }</p>
+                    <div class="now-playing-progress">
+                        <progress aria-label=move || t.get().playback_progress
+                            max=move || duration.get().as_secs_f64()
+                            value=move || elapsed.get().as_secs_f64()/>
+                        <span>{ move || format_duration(&elapsed.get(), locale.get()) }</span>
+                        <span>{ move || format_duration(&duration.get(), locale.get()) }</span>
+                    </div>
+                </div>
+            </Show>
+            <div class="controls now-playing-controls">
+                <button class="btn-round btn-large" disabled={
+                    let client = client.clone();
+                    move || !client.available(PlayerOp::Prev)
+                } on:click={
+                    let client = client.clone();
+                    move |_| { toasts.report(client.prev()); }}>
+                    <PreviousIcon frame=ICON_FRAME_LARGE/>
+                    <span class="screenreader-only">{ move || t.get().previous_track }</span>
+                </button>
+                <button class="btn-round btn-large" disabled={
+                    let client = client.clone();
+                    move || {
+                        if playing.get() {
+                            !client.available(PlayerOp::Pause)
+                        } else {
+                            !client.available(PlayerOp::Resume)
+                        }
+                    }
+                } on:click={
+                    let client = client.clone();
+                    move |_| {
+                        if playing.get_untracked() {
+                            toasts.report(client.pause());
+                        } else {
+                            toasts.report(client.resume());
+                        }
+                    }}>
+                    <Show when=move || playing.get()
+                          fallback=move || view! { <PlayIcon frame=ICON_FRAME_LARGE/> <span class="screenreader-only">{ move || t.get().resume }</span> }>
+                        <PauseIcon frame=ICON_FRAME_LARGE/>
+                        <span class="screenreader-only">{ move || t.get().pause }</span>
+                    </Show>
+                </button>
+                <button class="btn-round btn-large" disabled={
+                    let client = client.clone();
+                    move || !client.available(PlayerOp::Skip)
+                } on:click={
+                    let client = client.clone();
+                    move |_| { toasts.report(client.skip()); }}>
+                    <NextIcon frame=ICON_FRAME_LARGE/>
+                    <span class="screenreader-only">{ move || t.get().next_track }</span>
+                </button>
+            </div>
+            <Show when=move || snapshot.with(|s| !s.queue().is_empty())>
+                <section class="now-playing-queue">
+                    <h3>{ move || t.get().up_next }</h3>
+                    <ol>
+                        <For each=up_next key=|(idx, entry)| (*idx, entry.id().to_string()) let:((_, entry))>
+                            <li><TrackCard track=Signal::stored(entry)/></li>
+                        </For>
+                    </ol>
+                </section>
+            </Show>
+        </dialog>
+    }
+}
+
+#[component]
+fn RelinkDialog(
+    error: Signal<Option<ConnectionError>>,
+    set_saved: WriteSignal<Vec<SavedSession>>,
+    set_session: WriteSignal<Option<SessionParams>>,
+) -> impl IntoView {
+    let (link, set_link) = signal(String::new());
+    let (link_error, set_link_error) = signal(false);
+    let t = use_messages();
+    let dialog = NodeRef::<Dialog>::new();
+    Effect::new(move |_| {
+        if let Some(dialog) = dialog.get() {
+            if let Err(e) = dialog.show_modal() {
+                logging::error!("Cannot open dialog: {:?}", e);
+            }
+        }
+    });
+    view! {
+        <Show when=move || error.with(Option::is_some)>
+            <dialog class="relink-dialog" node_ref=dialog on:cancel=|e: ev::Event| e.prevent_default()>
+                <p>{ move || error.get().map(|e| match e {
+                    ConnectionError::Unauthorized => t.get().session_expired,
+                }) }</p>
+                <form on:submit=move |e| {
+                    e.prevent_default();
+                    match SessionParams::from_link(&link.get()) {
+                        Ok(params) => {
+                            // Replace the expired token of the saved session, if there is one.
+                            set_saved.update(|saved| {
+                                if saved.iter().any(|s| s.params.same_session(&params)) {
+                                    save_session(saved, params.clone(), None);
+                                }
+                            });
+                            set_session.set(Some(params));
+                        }
+                        Err(e) => {
+                            logging::warn!("Invalid session link: {}", e);
+                            set_link_error.set(true);
+                        }
+                    }
+                }>
+                    <label>
+                        <span>{ move || t.get().paste_new_link }</span>
+                        <input type="url" required autofocus
+                            prop:value=link
+                            on:input=move |e| set_link.set(event_target_value(&e))/>
+                    </label>
+                    <p class="error">{ move || link_error.get().then(|| t.get().invalid_link) }</p>
+                    <button type="submit">{ move || t.get().reconnect }</button>
+                </form>
+            </dialog>
+        </Show>
+    }
+}
+
+#[component]
+fn PlayerView(
+    params: SessionParams,
+    saved: Signal<Vec<SavedSession>>,
+    set_saved: WriteSignal<Vec<SavedSession>>,
+    set_session: WriteSignal<Option<SessionParams>>,
+    toasts: Toasts,
+) -> impl IntoView {
+    let client = RemotePlayer::new(
+        &params.remote_url(),
+        &params.token,
+        params.access_code,
+        params.role,
+    );
+    let snapshot = client.state;
+    let (notify, set_notify, _) = use_local_storage::<bool, FromToStringCodec>(NOTIFICATIONS_KEY);
+    let t = use_messages();
+    let locale = use_locale();
+    Effect::new(move |_| {
+        if let Some(e) = client.server_error.get() {
+            toasts.push((t.get_untracked().server_error)(&e.message));
+        }
+    });
+
+    let allows = |op| {
+        let client = client.clone();
+        Signal::derive(move || client.allows(op))
+    };
+    let can_remove = allows(PlayerOp::Remove);
+    let can_batch = allows(PlayerOp::Batch);
+    let can_clear = allows(PlayerOp::Clear);
+    let can_remove_where = allows(PlayerOp::RemoveWhere);
+    let can_enqueue = allows(PlayerOp::Enqueue);
+    let track_list = NodeRef::<Main>::new();
+    let (filter, set_filter) = signal(String::new());
+    let owner = RwSignal::new(None::<RemoveFilter>);
+    let owners = Memo::new(move |_| {
+        snapshot.with(|s| {
+            let mut owners = BTreeMap::new();
+            for entry in s.queue() {
+                let uploader = RemoveFilter::Uploader(entry.uploader().to_string());
+                owners.insert(uploader, (t.get().uploaded_by)(entry.uploader()));
+                if let Some(r) = entry.requester() {
+                    let requester = RemoveFilter::Requester(r.id.to_string());
+                    owners.insert(requester, (t.get().requested_by)(r.display_name));
+                }
+            }
+            owners.into_iter().collect::<Vec<_>>()
+        })
+    });
+    let visible = Memo::new(move |_| {
+        let query = filter.get();
+        let owner = owner.get();
+        snapshot.with(|s| {
+            let queue = s.queue().iter().enumerate();
+            queue
+                .filter(|(_, entry)| query.is_empty() || track_matches(*entry, &query))
+                .filter(|(_, entry)| owner.as_ref().is_none_or(|o| o.matches(*entry)))
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>()
+        })
+    });
+    let owned = Memo::new(move |_| {
+        let owner = owner.get();
+        snapshot.with(|s| {
+            let queue = s.queue().iter();
+            queue
+                .filter(|entry| owner.as_ref().is_some_and(|o| o.matches(*entry)))
+                .count()
+        })
+    });
+    let current_id =
+        Memo::new(move |_| snapshot.with(|s| s.current.as_ref().map(|c| c.id().to_string())));
+    let now_playing = RwSignal::new(false);
+    let details = RwSignal::new(None::<DetailsTarget>);
+    let import = RwSignal::new(None::<ImportedPlaylist>);
+    let queue_total = Memo::new(move |_| {
+        snapshot.with(|s| {
+            s.queue()
+                .iter()
+                .map(TrackSnapshot::duration)
+                .sum::<Duration>()
+        })
+    });
+    let started_at = use_track_start(
+        Signal::derive(move || snapshot.with(|s| s.position())),
+        Signal::derive(move || snapshot.with(|s| s.state() == MusicPlayerState::Playing)),
+    );
+    let selecting = RwSignal::new(false);
+    let selected = RwSignal::new(HashSet::<String>::new());
+    let anchor = StoredValue::new(None::<usize>);
+    let undo = RwSignal::new(None);
+    let undo_count = StoredValue::new(0);
+    let offer_undo = move |message: String, tracks| {
+        let id = undo_count.get_value() + 1;
+        undo_count.set_value(id);
+        undo.set(Some(Undo {
+            id,
+            message,
+            tracks,
+        }));
+        set_timeout(
+            move || {
+                if undo.with_untracked(|u: &Option<Undo<_>>| u.as_ref().is_some_and(|u| u.id == id))
+                {
+                    undo.set(None);
+                }
+            },
+            UNDO_TIMEOUT,
+        );
+    };
+    let restore = {
+        let client = client.clone();
+        move |_| {
+            if let Some(Undo { tracks, .. }) = undo.get_untracked() {
+                if toasts.report(client.restore(&tracks)) {
+                    undo.set(None);
+                }
+            }
+        }
+    };
+    let apply_batch = {
+        let client = client.clone();
+        move |action| {
+            let tracks: Vec<_> = snapshot.with(|s| {
+                let queue = s.queue().iter().enumerate();
+                selected.with(|sel| {
+                    queue
+                        .filter(|(_, e)| sel.contains(e.id()))
+                        .map(|(i, e)| (i, e.clone()))
+                        .collect()
+                })
+            });
+            let ids: Vec<_> = tracks.iter().map(|(_, e)| e.id().to_string()).collect();
+            let removing = action == QueueAction::Remove;
+            let undoable = client.available(PlayerOp::Restore);
+            let t = t.get_untracked();
+            if removing && !confirm_destructive(undoable, &(t.confirm_remove)(ids.len())) {
+                return;
+            }
+            if !toasts.report(client.batch(action, &ids)) {
+                return;
+            }
+            if removing && undoable {
+                offer_undo((t.removed_tracks)(tracks.len()), tracks);
+            }
+            selected.update(HashSet::clear);
+        }
+    };
+    view! {
+        <div class="container"
+            on:dragover=move |e: ev::DragEvent| {
+                if can_enqueue.get_untracked() {
+                    e.prevent_default();
+                }
+            }
+            on:drop=move |e: ev::DragEvent| {
+                let Some(data) = e.data_transfer().filter(|_| can_enqueue.get_untracked()) else {
+                    return;
+                };
+                if let Some(file) = data.files().and_then(|files| files.get(0)) {
+                    e.prevent_default();
+                    read_playlist(&file, import);
+                } else if let Some(links) = data.get_data("text/uri-list").ok().filter(|l| !l.is_empty()) {
+                    e.prevent_default();
+                    import.set(Some(playlist::import(&links)));
+                }
+            }>
+            <header class="header">
+                <span>
+                    { move || t.get().next_up }
+                    <span class="queue-summary">{ move || {
+                        let total = format_duration(&queue_total.get(), locale.get());
+                        let summary = (t.get().queue_summary)(snapshot.with(|s| s.queue().len()), &total);
+                        if snapshot.with(|s| s.loop_enabled()) {
+                            format!("{summary} · {}", t.get().queue_repeats)
+                        } else {
+                            summary
+                        }
+                    }}</span>
+                </span>
+                <div class="header-actions">
+                    <SessionSwitcher current=params.clone() saved set_saved set_session/>
+                    <button class="btn-inline" on:click=move |_| now_playing.set(true)>
+                        <ExpandIcon frame=ICON_FRAME_SMALL/>
+                        <span class="screenreader-only">{ move || t.get().show_now_playing }</span>
+                    </button>
+                    <button class="btn-inline" popovertarget="settings-dialog">
+                        <SettingsIcon frame=ICON_FRAME_SMALL/>
+                        <span class="screenreader-only">{ move || t.get().show_settings }</span>
+                    </button>
+                    <button class="btn-inline" popovertarget="copyright-dialog">
+                        <InfoIcon frame=ICON_FRAME_SMALL/>
+                        <span class="screenreader-only">{ move || t.get().show_copyright }</span>
+                    </button>
+                </div>
+            </header>
+            <div class="queue-toolbar">
+                <label class="queue-filter">
+                    <span class="screenreader-only">{ move || t.get().filter_queue }</span>
+                    <input type="search" placeholder=move || t.get().filter_queue
+                        prop:value=filter
+                        on:input=move |e| set_filter.set(event_target_value(&e))/>
+                </label>
+                <label class="owner-filter">
+                    <span class="screenreader-only">{ move || t.get().filter_owner }</span>
+                    <select on:change=move |e| {
+                        let idx = event_target_value(&e).parse::<usize>().ok();
+                        owner.set(idx.and_then(|i| owners.with(|o| o.get(i).map(|(f, _)| f.clone()))));
+                    }>
+                        <option value="" selected=move || owner.with(Option::is_none)>{ move || t.get().everyone }</option>
+                        <For each=move || owners.get().into_iter().enumerate()
+                             key=|(idx, (_, label))| (*idx, label.clone())
+                             let:((idx, (filter, label)))>
+                            <option value=idx.to_string() selected=move || owner.with(|o| o.as_ref() == Some(&filter))>
+                                { label }
+                            </option>
+                        </For>
+                    </select>
+                </label>
+                {
+                    let client = client.clone();
+                    view! {
+                        <Show when=move || can_remove_where.get() && owner.with(Option::is_some)>
+                            <button class="btn-text" disabled={
+                                    let client = client.clone();
+                                    move || !client.available(PlayerOp::RemoveWhere)
+                                } on:click={
+                                    let client = client.clone();
+                                    move |_| {
+                                        let Some(filter) = owner.get() else {
+                                            return;
+                                        };
+                                        let tracks: Vec<_> = snapshot.with(|s| {
+                                            let queue = s.queue().iter().enumerate();
+                                            queue.filter(|(_, e)| filter.matches(*e)).map(|(i, e)| (i, e.clone())).collect()
+                                        });
+                                        let undoable = client.available(PlayerOp::Restore);
+                                        let t = t.get_untracked();
+                                        if !confirm_destructive(undoable, &(t.confirm_remove)(tracks.len())) {
+                                            return;
+                                        }
+                                        if !toasts.report(client.remove_where(&filter)) {
+                                            return;
+                                        }
+                                        owner.set(None);
+                                        if undoable {
+                                            offer_undo((t.removed_tracks)(tracks.len()), tracks);
+                                        }
+                                    }}>
+                                { move || (t.get().remove_all)(owned.get()) }
+                            </button>
+                        </Show>
+                    }
+                }
+                <button class="btn-text" popovertarget="export-dialog">{ move || t.get().export_queue }</button>
+                <Show when=move || can_enqueue.get()>
+                    <button class="btn-text" popovertarget="import-dialog">{ move || t.get().import_queue }</button>
+                </Show>
+                {
+                    let client = client.clone();
+                    view! {
+                        <Show when=move || can_batch.get()>
+                            <button class="btn-text" disabled={
+                                    let client = client.clone();
+                                    move || !client.available(PlayerOp::Batch)
+                                } on:click=move |_| {
+                                    selecting.update(|s| *s = !*s);
+                                    selected.update(HashSet::clear);
+                                    anchor.set_value(None);
+                                }>
+                                { move || if selecting.get() { t.get().done } else { t.get().select } }
+                            </button>
+                        </Show>
+                    }
+                }
+                <Show when=move || can_batch.get() && selecting.get()>
+                    <div class="bulk-actions">
+                        <span>{ move || (t.get().selected_count)(selected.with(HashSet::len)) }</span>
+                        <button class="btn-text" on:click={
+                            let apply_batch = apply_batch.clone();
+                            move |_| apply_batch(QueueAction::Remove)}>
+                            { move || t.get().remove }
+                        </button>
+                        <button class="btn-text" on:click={
+                            let apply_batch = apply_batch.clone();
+                            move |_| apply_batch(QueueAction::MoveToTop)}>
+                            { move || t.get().move_to_top }
+                        </button>
+                        <button class="btn-text" on:click={
+                            let apply_batch = apply_batch.clone();
+                            move |_| apply_batch(QueueAction::MoveToBottom)}>
+                            { move || t.get().move_to_bottom }
+                        </button>
+                    </div>
+                </Show>
+            </div>
+            <main class="track-list" node_ref=track_list>
+                <VirtualList container=track_list
+                    len=Signal::derive(move || visible.with(Vec::len))
+                    slice=move |range| visible.with(|v| snapshot.with(|s| {
+                        v.get(range).unwrap_or_default().iter().map(|&i| (i, s.queue()[i].clone())).collect()
+                    }))
+                    row={
+                        let client = client.clone();
+                        move |idx, entry| {
+                            let id = entry.id().to_string();
+                            let remove = {
+                                let entry = entry.clone();
+                                let client = client.clone();
+                                move |_| {
+                                    let undoable = client.available(PlayerOp::Restore);
+                                    let t = t.get_untracked();
+                                    if !confirm_destructive(undoable, &(t.confirm_remove)(1)) {
+                                        return;
+                                    }
+                                    let idx = idx.get_untracked();
+                                    if toasts.report(client.remove(idx, entry.id())) && undoable {
+                                        offer_undo((t.removed_track)(entry.title()), vec![(idx, entry.clone())]);
+                                    }
+                                }
+                            };
+                            let remove_disabled = {
+                                let client = client.clone();
+                                move || !client.available(PlayerOp::Remove)
+                            };
+                            view! {
+                                <li value=move || idx.get() + 1>
+                                    <div class="track">
+                                        <Show when=move || can_batch.get() && selecting.get()>
+                                            <input type="checkbox" class="track-select"
+                                                prop:checked={
+                                                    let id = id.clone();
+                                                    move || selected.with(|s| s.contains(&id))
+                                                }
+                                                on:click={
+                                                    let id = id.clone();
+                                                    move |e: MouseEvent| {
+                                                        let checked = event_target_checked(&e);
+                                                        let idx = idx.get_untracked();
+                                                        let ids = match anchor.get_value().filter(|_| e.shift_key()) {
+                                                            Some(from) => visible.with(|v| snapshot.with(|s| {
+                                                                let range = from.min(idx)..=from.max(idx);
+                                                                v.iter().filter(|i| range.contains(i)).map(|&i| s.queue()[i].id().to_string()).collect()
+                                                            })),
+                                                            None => vec![id.clone()],
+                                                        };
+                                                        selected.update(|s| for id in ids {
+                                                            if checked { s.insert(id); } else { s.remove(&id); }
+                                                        });
+                                                        anchor.set_value(Some(idx));
+                                                    }}/>
+                                            <span class="screenreader-only">{ move || t.get().select }</span>
+                                        </Show>
+                                        <TrackCard track=Signal::stored(entry.clone()) highlight=filter.into()/>
+                                        <div class="track-controls">
+                                            { move || {
+                                                let started_at = started_at.get()?;
+                                                let start = snapshot.with(|s| Some(estimated_start(started_at, s.current.as_ref()?, s.queue(), idx.get())))?;
+                                                let time = format_time(start, locale.get());
+                                                Some(view! {
+                                                    <span class="track-start" title=(t.get().approximately)(&time)>
+                                                        <span class="screenreader-only">{ t.get().starts_at }</span>
+                                                        { time.clone() }
+                                                    </span>
+                                                })
+                                            }}
+                                            <span class="track-duration">{
+                                                let duration = entry.duration();
+                                                move || format_duration(&duration, locale.get())
+                                            }</span>
+                                            <button class="btn-inline" disabled={
+                                                    let client = client.clone();
+                                                    move || !client.available(PlayerOp::Move)
+                                                } on:click={
+                                                    let entry = entry.clone();
+                                                    let client = client.clone();
+                                                    move |_| { toasts.report(client.move_to(idx.get_untracked(), entry.id())); }}>
+                                                <PlayIcon frame=ICON_FRAME_SMALL/>
+                                                <span class="screenreader-only">{ move || t.get().play }</span>
+                                            </button>
+                                            <button class="btn-inline" popovertarget="track-details" popovertargetaction="show" on:click={
+                                                let id = entry.id().to_string();
+                                                move |_| details.set(Some(DetailsTarget { index: Some(idx.get_untracked()), id: id.clone() }))}>
+                                                <InfoIcon frame=ICON_FRAME_SMALL/>
+                                                <span class="screenreader-only">{ move || t.get().show_details }</span>
+                                            </button>
+                                            <Show when=move || can_remove.get()>
+                                                <button class="btn-inline" disabled=remove_disabled.clone() on:click=remove.clone()>
+                                                    <DeleteIcon frame=ICON_FRAME_SMALL/>
+                                                    <span class="screenreader-only">{ move || t.get().remove }</span>
+                                                </button>
+                                            </Show>
+                                        </div>
+                                    </div>
+                                </li>
+                            }
+                        }
+                    }/>
             </main>
             <footer class="footer">
                 <div class="track">
-                    <Show when=move || { snapshot.get().current.is_some() }>
-                        <TrackCard track=Signal::derive(move || { snapshot.get().current.unwrap() })/>
-                    </Show>
+                    // Only rebuild the card when the track changes, not on every position update.
+                    { move || current_id.get().and_then(|id| {
+                        let current = snapshot.with_untracked(|s| s.current.clone())?;
+                        Some(view! {
+                            <TrackCard track=Signal::stored(current)/>
+                            <button class="btn-inline" popovertarget="track-details" popovertargetaction="show"
+                                on:click=move |_| details.set(Some(DetailsTarget { index: None, id: id.clone() }))>
+                                <InfoIcon frame=ICON_FRAME_SMALL/>
+                                <span class="screenreader-only">{ move || t.get().show_details }</span>
+                            </button>
+                        })
+                    }) }
                 </div>
                 <div class="controls">
-                    <button class="btn-round" on:click={
+                    {
                         let client = client.clone();
-                        move |_| { client.clear().unwrap(); }}>
-                        <DeleteIcon frame=ICON_FRAME_LARGE/>
-                        <span class="screenreader-only">Clear queue</span>
-                    </button>
-                    <button class="btn-round" on:click={
+                        view! {
+                            <Show when=move || can_clear.get()>
+                                <button class="btn-round" disabled={
+                                    let client = client.clone();
+                                    move || !client.available(PlayerOp::Clear)
+                                } on:click={
+                                    let client = client.clone();
+                                    move |_| {
+                                        let undoable = client.available(PlayerOp::Restore);
+                                        let t = t.get_untracked();
+                                        if !confirm_destructive(undoable, t.confirm_clear) {
+                                            return;
+                                        }
+                                        let tracks: Vec<_> = snapshot.with(|s| s.queue().iter().cloned().enumerate().collect());
+                                        if toasts.report(client.clear()) && undoable {
+                                            offer_undo(t.queue_cleared.to_string(), tracks);
+                                        }
+                                    }}>
+                                    <DeleteIcon frame=ICON_FRAME_LARGE/>
+                                    <span class="screenreader-only">{ move || t.get().clear_queue }</span>
+                                </button>
+                            </Show>
+                        }
+                    }
+                    <button class="btn-round" disabled={
+                        let client = client.clone();
+                        move || !client.available(PlayerOp::Prev)
+                    } on:click={
                         let client = client.clone();
-                        move |_| { client.prev().unwrap(); }}>
+                        move |_| { toasts.report(client.prev()); }}>
                         <PreviousIcon frame=ICON_FRAME_LARGE/>
-                        <span class="screenreader-only">Previous track</span>
+                        <span class="screenreader-only">{ move || t.get().previous_track }</span>
                     </button>
-                    <button class="btn-round" on:click={
+                    <button class="btn-round" disabled={
+                        let client = client.clone();
+                        move || {
+                            if snapshot.get().state() == MusicPlayerState::Playing {
+                                !client.available(PlayerOp::Pause)
+                            } else {
+                                !client.available(PlayerOp::Resume)
+                            }
+                        }
+                    } on:click={
                         let client = client.clone();
                         move |_| {
                             if snapshot.get().state() == MusicPlayerState::Playing {
-                                client.pause().unwrap();
+                                toasts.report(client.pause());
                             } else {
-                                client.resume().unwrap();
+                                toasts.report(client.resume());
                             }
                         }}>
                         <Show when=move || { snapshot.get().state() == MusicPlayerState::Playing }
-                              fallback=|| view! { <PlayIcon frame=ICON_FRAME_LARGE/> <span class="screenreader-only">Resume</span> }>
+                              fallback=move || view! { <PlayIcon frame=ICON_FRAME_LARGE/> <span class="screenreader-only">{ move || t.get().resume }</span> }>
                             <PauseIcon frame=ICON_FRAME_LARGE/>
-                            <span class="screenreader-only">Pause</span>
+                            <span class="screenreader-only">{ move || t.get().pause }</span>
                         </Show>
                     </button>
-                    <button class="btn-round" on:click={
+                    <button class="btn-round" disabled={
                         let client = client.clone();
-                        move |_| { client.skip().unwrap(); }}>
+                        move || !client.available(PlayerOp::Skip)
+                    } on:click={
+                        let client = client.clone();
+                        move |_| { toasts.report(client.skip()); }}>
                         <NextIcon frame=ICON_FRAME_LARGE/>
-                        <span class="screenreader-only">Next track</span>
+                        <span class="screenreader-only">{ move || t.get().next_track }</span>
                     </button>
-                    <label class="btn-round">
-                        <input type="checkbox"
-                            prop:checked=move || { snapshot.get().loop_enabled() }
-                            on:change={
-                                let client = client.clone();
-                                move |e| { client.set_loop(event_target_checked(&e)).unwrap(); }}/>
-                        <LoopIcon frame=ICON_FRAME_LARGE/>
-                        <span class="screenreader-only">Loop</span>
-                    </label>
-                </div>
-                <label class="volume-widget">
-                    <VolumeIcon value=Signal::derive(move || snapshot.get().volume())/>
-                    <span class="screenreader-only">Volume</span>
-                    <input type="range" id="volume" min="0" max="100" step="1"
-                        prop:value=move || { snapshot.get().volume() }
-                        on:change={
+                    <LoopButton
+                        enabled=Signal::derive(move || snapshot.get().loop_enabled())
+                        disabled=Signal::derive({
                             let client = client.clone();
-                            move |e| { client.set_volume(event_target_value(&e).parse().unwrap()).unwrap(); }}/>
-                </label>
+                            move || !client.available(PlayerOp::Loop)
+                        })
+                        on_toggle={
+                            let client = client.clone();
+                            move |enabled| { toasts.report(client.set_loop(enabled)); }}/>
+                </div>
+                <VolumeSlider
+                    volume=Signal::derive(move || snapshot.get().volume())
+                    disabled=Signal::derive({
+                        let client = client.clone();
+                        move || !client.available(PlayerOp::Volume)
+                    })
+                    on_change={
+                        let client = client.clone();
+                        move |value: String| {
+                            match value.parse() {
+                                Ok(volume) => {
+                                    toasts.report(client.set_volume(volume));
+                                }
+                                Err(e) => {
+                                    logging::error!("Invalid volume: {}", e);
+                                    toasts.push(t.get_untracked().command_failed.to_string());
+                                }
+                            }
+                        }}/>
             </footer>
             <dialog id="copyright-dialog" class="copyright-dialog" popover>
                 <pre>{ COPYRIGHT_INFO }</pre>
-                <p><a href="https://github.com/kmolski/acme-bot-remote" target="_blank">Show source code</a></p>
-                <p><a href="./license_info.html" target="_blank">Show OSS licenses</a></p>
-                <button popovertarget="copyright-dialog">Close</button>
+                <p><a href="https://github.com/kmolski/acme-bot-remote" target="_blank" rel="noreferrer noopener">{ move || t.get().show_source }</a></p>
+                <p><a href="./license_info.html" target="_blank">{ move || t.get().show_licenses }</a></p>
+                <button popovertarget="copyright-dialog" autofocus>{ move || t.get().close }</button>
             </dialog>
+            <SettingsDialog client=client.clone() toasts params notify set_notify/>
+            <TrackDetails client=client.clone() target=details started_at/>
+            <ExportDialog client=client.clone() toasts/>
+            <ImportDialog client=client.clone() toasts import/>
+            <Show when=move || notify.get()>
+                <TrackNotifier current=Signal::derive(move || snapshot.with(|s| s.current.clone()))/>
+            </Show>
+            <ToastList toasts>
+                <Show when=move || undo.with(Option::is_some)>
+                    <div class="toast" role="status">
+                        <span>{ move || undo.with(|u| u.as_ref().map(|u| u.message.clone())) }</span>
+                        <button class="btn-text" on:click=restore.clone()>{ move || t.get().undo }</button>
+                        <button class="btn-text" on:click=move |_| undo.set(None)>{ move || t.get().dismiss }</button>
+                    </div>
+                </Show>
+            </ToastList>
+            {
+                let client = client.clone();
+                view! {
+                    <Show when=move || now_playing.get()>
+                        <NowPlaying client=client.clone() toasts open=now_playing/>
+                    </Show>
+                }
+            }
+            <RelinkDialog error=client.error set_saved set_session/>
+            <LiveAnnouncer
+                current=Signal::derive(move || snapshot.with(|s| s.current.clone()))
+                state=Signal::derive(move || snapshot.with(|s| s.state()))/>
         </div>
     }
 }
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use leptos::mount::mount_to;
+    use leptos::task::tick;
+    use leptos::web_sys::HtmlElement;
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::player::SessionRole;
+    use crate::remote_api::{queue_entry, QueueEntry};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn container() -> HtmlElement {
+        let div = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&div).unwrap();
+        div.unchecked_into()
+    }
+
+    fn attribute(parent: &HtmlElement, selector: &str, name: &str) -> Option<String> {
+        let element = parent.query_selector(selector).unwrap()?;
+        element.get_attribute(name)
+    }
+
+    #[wasm_bindgen_test]
+    async fn loop_button_is_pressed_when_enabled() {
+        let parent = container();
+        let enabled = RwSignal::new(false);
+        let _handle = mount_to(parent.clone(), move || {
+            view! { <LoopButton enabled=enabled.into() disabled=Signal::stored(false) on_toggle=|_| {}/> }
+        });
+        assert_eq!(
+            attribute(&parent, "button", "aria-pressed").as_deref(),
+            Some("false")
+        );
+        enabled.set(true);
+        tick().await;
+        assert_eq!(
+            attribute(&parent, "button", "aria-pressed").as_deref(),
+            Some("true")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn volume_slider_announces_percent() {
+        let parent = container();
+        let volume = RwSignal::new(40);
+        let _handle = mount_to(parent.clone(), move || {
+            view! { <VolumeSlider volume=volume.into() disabled=Signal::stored(false) on_change=|_| {}/> }
+        });
+        assert_eq!(
+            attribute(&parent, "input", "aria-valuetext").as_deref(),
+            Some("40%")
+        );
+        volume.set(75);
+        tick().await;
+        assert_eq!(
+            attribute(&parent, "input", "aria-valuetext").as_deref(),
+            Some("75%")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn track_card_describes_thumbnail() {
+        let parent = container();
+        let track = queue_entry(
+            "dQw4w9WgXcQ",
+            "Never Gonna Give You Up",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+        );
+        let _handle = mount_to(parent.clone(), move || {
+            view! { <TrackCard track=Signal::stored(track)/> }
+        });
+        assert_eq!(
+            attribute(&parent, "img", "alt").as_deref(),
+            Some("Thumbnail of Never Gonna Give You Up")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn live_announcer_announces_new_track() {
+        let parent = container();
+        let current = RwSignal::new(Some(queue_entry("a", "First", "https://example.com/a")));
+        let track: Signal<Option<QueueEntry>> = current.into();
+        let _handle = mount_to(parent.clone(), move || {
+            view! { <LiveAnnouncer current=track state=Signal::stored(MusicPlayerState::Playing)/> }
+        });
+        tick().await;
+        let status = parent.query_selector("[role=status]").unwrap().unwrap();
+        assert_eq!(status.text_content().unwrap_or_default(), "");
+        current.set(Some(queue_entry("b", "Second", "https://example.com/b")));
+        tick().await;
+        assert_eq!(
+            status.text_content().unwrap_or_default(),
+            "Now playing: Second by Rick Astley"
+        );
+    }
+
+    /// Open the popover with the given ID, and get the ID or text of the focused element.
+    fn open_popover(parent: &HtmlElement, id: &str) -> String {
+        let dialog = parent.query_selector(&format!("#{id}")).unwrap().unwrap();
+        call_method(&dialog, "showPopover", &[]).unwrap();
+        let focused = document().active_element().unwrap();
+        let name = focused.get_attribute("type").unwrap_or_default();
+        format!(
+            "{}:{}{}",
+            focused.tag_name(),
+            name,
+            focused.text_content().unwrap_or_default()
+        )
+    }
+
+    fn client() -> RemotePlayer {
+        RemotePlayer::new("ws://127.0.0.1:9", "token", 1, SessionRole::Admin)
+    }
+
+    #[wasm_bindgen_test]
+    fn export_dialog_focuses_close() {
+        let parent = container();
+        let _handle = mount_to(parent.clone(), move || {
+            view! { <ExportDialog client=client() toasts=Toasts::new(use_messages())/> }
+        });
+        assert_eq!(open_popover(&parent, "export-dialog"), "BUTTON:Close");
+    }
+
+    #[wasm_bindgen_test]
+    fn import_dialog_focuses_file_input() {
+        let parent = container();
+        let _handle = mount_to(parent.clone(), move || {
+            let toasts = Toasts::new(use_messages());
+            view! { <ImportDialog client=client() toasts import=RwSignal::new(None)/> }
+        });
+        assert_eq!(open_popover(&parent, "import-dialog"), "INPUT:file");
+    }
+
+    #[wasm_bindgen_test]
+    fn track_details_focuses_close() {
+        let parent = container();
+        let _handle = mount_to(parent.clone(), move || {
+            let target = RwSignal::new(None);
+            view! { <TrackDetails client=client() target started_at=Memo::new(|_| None)/> }
+        });
+        assert_eq!(open_popover(&parent, "track-details"), "BUTTON:Close");
+    }
+}